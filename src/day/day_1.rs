@@ -1,10 +1,29 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
+use crate::reference;
 use crate::utils;
 
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
 use log::info;
 
+/// Day 1 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[Assumption {
+    name: "every line has a digit",
+    check: has_digits,
+}];
+
+/// Day 1 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_1,
+    },
+];
+
 /// Day 1 - Sum of calibration values.
 ///
 /// Part 1. On each line of the input, the calibration value can be found by combining
@@ -15,6 +34,15 @@ use log::info;
 /// one, two, three, four, five, six, seven, eight, and nine also count as valid "digits".
 /// Be warned, names can overlap! Ex. oneight should be both "one" and "eight"
 pub fn run(part: &Part) -> Result<usize, Report> {
+    // read in the input (calibration document)
+    let input = utils::read_to_string("data/day_1.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 1 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
     let digits = vec![
         ("one", "1"),
         ("two", "2"),
@@ -27,9 +55,7 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         ("nine", "9"),
     ];
 
-    // read in the input (calibration document)
-    let content = utils::read_to_string("data/day_1.txt")?;
-    let lines = content.split('\n').collect_vec();
+    let lines = input.split('\n').collect_vec();
 
     let mut total = 0;
 
@@ -58,8 +84,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         total += digit;
     }
 
-    info!("Answer: {total}");
-
     Ok(total)
 }
 
@@ -78,3 +102,15 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// Part 1 only searches for numeric digits, and each line needs at least one.
+fn has_digits(input: &str) -> Result<(), Report> {
+    match input.split('\n').position(|l| !l.chars().any(|c| c.is_ascii_digit())) {
+        Some(i) => Err(eyre!("Line {} has no digits.", i + 1)),
+        None => Ok(()),
+    }
+}
@@ -1,17 +1,57 @@
-use crate::day::Part;
-use crate::game::Map;
+use crate::day::{Assumption, Part, Variant};
+use crate::game::{polygon, Color, Grid, Map, Point, Region, Renderer, Style};
+use crate::reference;
 
 use crate::utils;
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 //use itertools::Itertools;
 use log::debug;
 use log::info;
 use std::str::FromStr;
 
+/// Day 10 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[
+    Assumption {
+        name: "there is exactly one start",
+        check: one_start,
+    },
+    Assumption {
+        name: "the start connects to exactly two pipes",
+        check: start_connections,
+    },
+];
+
+/// Day 10 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_10,
+    },
+    Variant {
+        name: "polygon",
+        solve: solve_polygon,
+    },
+    Variant {
+        name: "scanline",
+        solve: solve_scanline,
+    },
+];
+
 /// Day 10 - Pipe Maze
 pub fn run(part: &Part) -> Result<usize, Report> {
     let input = utils::read_to_string("data/day_10.txt")?;
-    let mut pipe_map = Map::from_str(&input)?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 10 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
+    let mut pipe_map = Map::from_str(input)?;
 
     // part 2, zoom in (ie. push pipes apart to go in between)
     if *part == Part::Part2 {
@@ -19,82 +59,96 @@ pub fn run(part: &Part) -> Result<usize, Report> {
     }
 
     // find start location
-    let y = pipe_map.tiles.iter().position(|row| row.contains(&'S')).unwrap();
-    let x = pipe_map.tiles[y].iter().position(|x| *x == 'S').unwrap();
+    let start = pipe_map.search(&'S')[0];
 
     // find the loop by flood fill, starting at 'S'
     debug!("Finding pipe loop.");
     let follow_pipes = true;
-    let pipe_loop = pipe_map.flood_fill(x, y, follow_pipes);
+    let pipe_loop = pipe_map.flood_fill(start, follow_pipes);
 
     // in part 2, find all tiles inside and outside the loop
     let (mut insiders, mut outsiders) = (Vec::new(), Vec::new());
     if *part == Part::Part2 {
-        let (x_max, y_max) = (pipe_map.tiles[0].len() - 1, pipe_map.tiles.len() - 1);
+        let (x_max, y_max) = (pipe_map.columns() - 1, pipe_map.rows() - 1);
 
-        // replace all non-pipe coords with '+', identify candidate I/O on even
+        // replace all non-pipe coords with '.', identify candidate I/O on even
         // numbered rows and columns (because we zoomed)
         debug!("Finding candidates.");
+        let mut on_loop = Grid::new(x_max + 1, y_max + 1, false);
+        pipe_loop.iter().for_each(|p| on_loop[*p] = true);
         let mut candidates = Vec::new();
         (0..=y_max).for_each(|y| {
             (0..=x_max).for_each(|x| {
-                if !pipe_loop.contains(&(x, y)) {
-                    pipe_map.tiles[y][x] = '.';
+                let p = Point::new(x, y);
+                if !on_loop[p] {
+                    pipe_map.tiles[p] = '.';
                     if x % 2 == 0 && y % 2 == 0 {
-                        candidates.push((x, y));
+                        candidates.push(p);
                     }
                 };
             })
         });
 
-        debug!("Flood filling candidates.");
-        while !candidates.is_empty() {
-            let (x, y) = candidates.first().cloned().unwrap();
-            let follow_pipes = false;
-            let mut filled = pipe_map.flood_fill(x, y, follow_pipes);
-            filled.retain(|(x, y)| candidates.contains(&(*x, *y)));
+        // label the ground regions, those that reach the edge are outside
+        debug!("Labeling regions.");
+        let regions = pipe_map.components(|c| *c == '.');
+        for p in candidates {
+            let region = regions.labels[p].unwrap();
+            match regions.touches_border[region] {
+                true => outsiders.push(p),
+                false => insiders.push(p),
+            };
+        }
+        debug!("{} inside, {} outside.", insiders.len(), outsiders.len());
+    }
 
-            // check if fill went to the edge (ie outside)
-            let fill_to_edge =
-                filled.iter().any(|(x, y)| *x == 0 || *x == x_max || *y == 0 || *y == y_max);
+    let result = match *part {
+        Part::Part1 => pipe_loop.len() / 2,
+        Part::Part2 => insiders.len(),
+    };
 
-            match fill_to_edge {
-                true => outsiders.append(&mut filled),
-                false => insiders.append(&mut filled),
-            };
+    Ok(result)
+}
 
-            candidates.retain(|c| !outsiders.contains(c) && !insiders.contains(c));
-        }
+/// Solve day 10 by tracing the loop as a polygon, and counting the tiles
+/// inside with the shoelace formula and Pick's theorem.
+pub fn solve_polygon(input: &str, part: &Part) -> Result<usize, Report> {
+    let pipe_map = Map::from_str(input)?;
+    let start = *pipe_map.search(&'S').first().ok_or_else(|| eyre!("No start."))?;
+    let (pipe_loop, tile) = pipe_map.trace_loop(start)?;
+    debug!("Loop of {} tiles, start is {tile:?}.", pipe_loop.len());
 
-        // // debugging, change chars to 'O' and 'I'
-        // pipe_map.tiles.iter_mut().enumerate().for_each(|(y, row)| {
-        //     *row = row
-        //         .iter_mut()
-        //         .enumerate()
-        //         .map(|(x, c)| {
-        //             if outsiders.contains(&(x, y)) {
-        //                 'O'
-        //             } else if insiders.contains(&(x, y)) {
-        //                 'I'
-        //             } else {
-        //                 *c
-        //             }
-        //         })
-        //         .collect_vec();
-        // });
-
-        // pipe_map.zoom_out();
-        // pipe_map.tiles.iter().for_each(|row| {
-        //     println!("{}", row.iter().join(""));
-        // });
+    let result = match *part {
+        Part::Part1 => pipe_loop.len() / 2,
+        // every tile of the loop is a vertex of the polygon
+        Part::Part2 => polygon::interior(&pipe_loop),
+    };
+
+    Ok(result)
+}
+
+/// Solve day 10 by scanning each row, counting crossings of the loop.
+pub fn solve_scanline(input: &str, part: &Part) -> Result<usize, Report> {
+    let pipe_map = Map::from_str(input)?;
+    let start = *pipe_map.search(&'S').first().ok_or_else(|| eyre!("No start."))?;
+    let regions = pipe_map.scanline(start)?;
+    let (pipe_loop, inside) = (
+        regions.positions(|r| *r == Region::Loop),
+        regions.positions(|r| *r == Region::Inside),
+    );
+
+    if log::log_enabled!(log::Level::Debug) {
+        let renderer = Renderer::pipes()
+            .overlay("Loop", pipe_loop.clone(), Style::foreground(Color::Cyan))
+            .overlay("Inside", inside.clone(), Style::background(Color::Green));
+        debug!("Regions:\n{}", renderer.render(&pipe_map));
     }
 
     let result = match *part {
         Part::Part1 => pipe_loop.len() / 2,
-        Part::Part2 => insiders.len(),
+        Part::Part2 => inside.len(),
     };
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
@@ -113,3 +167,24 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// The loop is found by flood filling from the first 'S'.
+fn one_start(input: &str) -> Result<(), Report> {
+    match input.matches('S').count() {
+        1 => Ok(()),
+        n => Err(eyre!("Found {n} starts.")),
+    }
+}
+
+/// The start could be any pipe, so the loop is only unambiguous if exactly two
+/// neighbors connect back to it.
+fn start_connections(input: &str) -> Result<(), Report> {
+    let map = Map::from_str(input)?;
+    let start = *map.search(&'S').first().ok_or_else(|| eyre!("No start."))?;
+    map.infer_start(start)?;
+    Ok(())
+}
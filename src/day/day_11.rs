@@ -1,24 +1,54 @@
-use crate::day::Part;
-use crate::game::Map;
+use crate::day::{Assumption, Part, Variant};
+use crate::game::{Map, Point, SparseMap};
+use crate::reference;
 use crate::utils;
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
 use log::debug;
 use log::info;
 use std::str::FromStr;
 
+/// Day 11 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[Assumption {
+    name: "there are at least two galaxies",
+    check: galaxy_pair,
+}];
+
+/// Day 11 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_11,
+    },
+    Variant {
+        name: "sparse",
+        solve: solve_sparse,
+    },
+];
+
 /// Day 11
 pub fn run(part: &Part) -> Result<usize, Report> {
     let input = utils::read_to_string("data/day_11.txt")?;
-    let galaxy_map = Map::from_str(&input)?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 11 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
+    let galaxy_map = Map::from_str(input)?;
 
     // find empty space, not occupied by galaxies
     let mut galaxies = galaxy_map.search(&'#');
-    let (gx, gy): (Vec<usize>, Vec<usize>) = galaxies.clone().into_iter().unzip();
+    let (gx, gy): (Vec<usize>, Vec<usize>) = galaxies.iter().map(|g| (g.x, g.y)).unzip();
 
-    let x_max = galaxy_map.tiles[0].len() - 1;
+    let x_max = galaxy_map.columns() - 1;
     let empty_x = (0..=x_max).filter(|x| !gx.contains(x)).unique().collect_vec();
-    let empty_y = (0..galaxy_map.tiles.len()).filter(|y| !gy.contains(y)).unique().collect_vec();
+    let empty_y = (0..galaxy_map.rows()).filter(|y| !gy.contains(y)).unique().collect_vec();
 
     // expand empty space, at different rates
     let e = match *part {
@@ -26,7 +56,8 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         Part::Part2 => 1000000,
     };
 
-    galaxies.iter_mut().enumerate().for_each(|(i, (gx, gy))| {
+    galaxies.iter_mut().enumerate().for_each(|(i, g)| {
+        let Point { x: gx, y: gy } = g;
         let (gx_orig, gy_orig) = (*gx, *gy);
         let xn = empty_x.iter().filter(|x| *x < gx).count();
         let yn = empty_y.iter().filter(|y| *y < gy).count();
@@ -49,9 +80,7 @@ pub fn run(part: &Part) -> Result<usize, Report> {
                 .iter()
                 .enumerate()
                 .map(|(i2, g2)| {
-                    let dist = ((g2.0 as isize - g1.0 as isize).abs()
-                        + (g2.1 as isize - g1.1 as isize).abs())
-                        as usize;
+                    let dist = g1.manhattan(g2);
                     debug!("\tg{}: {g2:?}, dist: {dist}", i1 + i2 + 2);
                     dist
                 })
@@ -59,10 +88,23 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         })
         .sum();
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
+/// Solve day 11 by expanding a sparse map of the galaxies directly.
+pub fn solve_sparse(input: &str, part: &Part) -> Result<usize, Report> {
+    let factor = match *part {
+        Part::Part1 => 2,
+        Part::Part2 => 1000000,
+    };
+    let galaxy_map = SparseMap::from_map(&Map::from_str(input)?, '.').expand(factor);
+    let galaxies = galaxy_map.positions(|c| *c == '#');
+    let pairs = galaxies.iter().tuple_combinations();
+    let result = pairs.map(|(g1, g2)| g1.manhattan(g2)).sum::<u64>();
+
+    Ok(result as usize)
+}
+
 #[test]
 fn part_1() -> Result<(), Report> {
     let expected = 9769724;
@@ -78,3 +120,15 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// Distances are summed over pairs of galaxies.
+fn galaxy_pair(input: &str) -> Result<(), Report> {
+    match input.matches('#').count() {
+        0 | 1 => Err(eyre!("Found fewer than two galaxies.")),
+        _ => Ok(()),
+    }
+}
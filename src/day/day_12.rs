@@ -1,4 +1,5 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
+use crate::reference;
 use crate::utils;
 
 use cached::{proc_macro::cached, UnboundCache};
@@ -6,12 +7,34 @@ use color_eyre::eyre::{Report, Result};
 use itertools::Itertools;
 use log::{debug, info};
 
+/// Day 12 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[];
+
+/// Day 12 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_12,
+    },
+];
+
 /// Day 12 - Hot Springs
 ///
 /// Recursions + memoization
 pub fn run(part: &Part) -> Result<usize, Report> {
-    // Parse puzzle input into lines
     let input = utils::read_to_string("data/day_12.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 12 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
+    // Parse puzzle input into lines
     let lines = input.split('\n').map(|l| l.split(' ').collect_vec()).collect_vec();
 
     // sum up the possible arrangements
@@ -38,7 +61,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         })
         .sum();
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
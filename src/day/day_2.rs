@@ -1,4 +1,5 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
+use crate::reference;
 use crate::utils;
 
 use aho_corasick::AhoCorasick;
@@ -7,6 +8,21 @@ use itertools::Itertools;
 use log::{debug, info};
 use std::collections::BTreeMap;
 
+/// Day 2 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[];
+
+/// Day 2 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_2,
+    },
+];
+
 /// Day 2. Cubes of Power
 ///
 /// The Elf will reach into the bag, grab a handful of random cubes, show
@@ -22,6 +38,14 @@ use std::collections::BTreeMap;
 /// of cubes is equal to the numbers of red, green, and blue cubes
 /// multiplied together. What is the sum of the power of these sets?
 pub fn run(part: &Part) -> Result<usize, Report> {
+    let document = utils::read_to_string("data/day_2.txt")?;
+    let result = solve(&document, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 2 from the puzzle input text.
+pub fn solve(document: &str, part: &Part) -> Result<usize, Report> {
     // part 1 hypothesis, max cube counts for a possible game
     let hypothesis: BTreeMap<&str, usize> =
         vec![("red", 12), ("green", 13), ("blue", 14)].into_iter().collect();
@@ -29,10 +53,8 @@ pub fn run(part: &Part) -> Result<usize, Report> {
     // read in the input, remove delimiter chars other than space
     // parse into lines: "Game 1: 2 green, 6 blue, ..."
     // parse into space delimited lists ["Game", "1", "2", "green", ...]
-
-    let document = utils::read_to_string("data/day_2.txt")?;
     let ac = AhoCorasick::builder().build([":", ",", ";"]).unwrap();
-    let content = ac.replace_all(&document, &["", "", ""]);
+    let content = ac.replace_all(document, &["", "", ""]);
     let lines = content.split('\n').collect_vec();
     let lines_split = lines.iter().map(|line| line.split(' ').collect_vec()).collect_vec();
 
@@ -44,7 +66,9 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         let id: usize = line[1].parse()?;
 
         let mut possible = true;
-        let mut max_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        // a color that never shows up has a max count of 0
+        let mut max_counts: BTreeMap<&str, usize> =
+            hypothesis.keys().map(|cube| (*cube, 0)).collect();
 
         let observations = (2..line.len())
             .step_by(2)
@@ -58,9 +82,9 @@ pub fn run(part: &Part) -> Result<usize, Report> {
                     possible = false
                 }
                 // check part 2, max counts
-                let max = max_counts.entry(cube).or_insert(observed);
+                let max = max_counts.entry(cube).or_default();
                 if observed > *max {
-                    max_counts.insert(cube, observed);
+                    *max = observed;
                 }
                 (cube, observed)
             })
@@ -84,7 +108,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         Part::Part2 => power_sum,
     };
 
-    info!("Answer: {answer}");
     Ok(answer)
 }
 
@@ -1,11 +1,27 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
 use crate::game::{Map, Tile};
+use crate::reference;
 
 use color_eyre::eyre::{Report, Result};
 use itertools::Itertools;
 use log::info;
 use std::str::FromStr;
 
+/// Day 3 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[];
+
+/// Day 3 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_3,
+    },
+];
+
 /// Day 3
 ///
 /// The original puzzle involved an "engine schematic". I thought it would be more
@@ -15,9 +31,16 @@ use std::str::FromStr;
 /// Symbol => Enemy
 /// Period = Base
 pub fn run(part: &Part) -> Result<usize, Report> {
-    // parse input file into game map
     let input = std::fs::read_to_string("data/day_3.txt")?;
-    let map = Map::from_str(&input)?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 3 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
+    // parse input file into game map
+    let map = Map::from_str(input)?;
 
     // locate all our map tiles of interest
     let traps = map.find_tile(&Tile::Trap).into_iter().flatten().collect_vec();
@@ -31,11 +54,9 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         // Part 1: Characters (part number) next to enemy (symbol)
         Part::Part1 => {
             for coords in &characters {
-                for (x, y) in coords {
-                    let mut n = map.get_neighbors(*x, *y);
-                    n.retain(|coord| enemies.contains(coord));
-                    if !n.is_empty() {
-                        let (number, _) = map.get_character(*x, *y).unwrap();
+                for p in coords {
+                    if map.get_neighbors(*p).any(|coord| enemies.contains(&coord)) {
+                        let (number, _) = map.get_character(*p).unwrap();
                         result += number;
                         break;
                     }
@@ -45,20 +66,13 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         // --------------------------------------------------------------------
         // Part 2: Traps (gear) next to exactly 2 characters (part numbers)
         Part::Part2 => {
-            for (x, y) in &traps {
-                let n = map.get_neighbors(*x, *y);
+            for p in &traps {
                 let n_characters = characters
                     .iter()
                     .filter_map(|coords| {
-                        let mut overlap = n.clone();
-                        overlap.retain(|c| coords.contains(c));
-                        if !overlap.is_empty() {
-                            let (char_x, char_y) = overlap[0];
-                            let (n, _) = map.get_character(char_x, char_y).unwrap();
-                            Some(n)
-                        } else {
-                            None
-                        }
+                        let mut overlap = map.get_neighbors(*p).filter(|c| coords.contains(c));
+                        let (n, _) = map.get_character(overlap.next()?).unwrap();
+                        Some(n)
                     })
                     .collect_vec();
                 if n_characters.len() == 2 {
@@ -68,7 +82,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         }
     }
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
@@ -1,14 +1,46 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
+use crate::reference;
 use crate::utils;
 
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
 use log::info;
 use std::str::FromStr;
 
+/// Day 4 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[
+    Assumption {
+        name: "card ids are contiguous and start at 1",
+        check: contiguous_ids,
+    },
+    Assumption {
+        name: "cards never win copies past the end of the table",
+        check: copies_in_deck,
+    },
+];
+
+/// Day 4 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_4,
+    },
+];
+
 /// Day 4
 pub fn run(part: &Part) -> Result<usize, Report> {
     let input = utils::read_to_string("data/day_4.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 4 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
     let deck = input.split('\n').filter_map(|c| Card::from_str(c).ok()).collect_vec();
 
     let result = match *part {
@@ -16,7 +48,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
         Part::Part2 => deck.iter().filter_map(|c| c.expand_cards(&deck).ok()).flatten().count(),
     };
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
@@ -100,3 +131,36 @@ impl FromStr for Card {
         Ok(card)
     }
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// Copies are looked up as `deck[id - 1]`, so the ids must match the card order.
+fn contiguous_ids(input: &str) -> Result<(), Report> {
+    for (i, line) in input.split('\n').enumerate() {
+        let card = Card::from_str(line)?;
+        if card.id != i + 1 {
+            return Err(eyre!(
+                "Card on line {} has id {}, expected {}.",
+                i + 1,
+                card.id,
+                i + 1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Copies are looked up as `deck[id - 1]`, so winnings can't go past the last card.
+fn copies_in_deck(input: &str) -> Result<(), Report> {
+    let deck = input.split('\n').map(Card::from_str).collect::<Result<Vec<_>, Report>>()?;
+    match deck.iter().find(|c| c.id + c.winning_numbers.len() > deck.len()) {
+        Some(card) => Err(eyre!(
+            "Card {} wins copies past the last card {}.",
+            card.id,
+            deck.len()
+        )),
+        None => Ok(()),
+    }
+}
@@ -1,18 +1,44 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
+use crate::reference;
 use crate::utils;
 
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use core::ops::Range;
 use itertools::Itertools;
 use log::info;
 use std::cmp::{max, min};
 
+/// Day 5 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[Assumption {
+    name: "source ranges in a map don't overlap",
+    check: disjoint_sources,
+}];
+
+/// Day 5 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_5,
+    },
+];
+
 /// Day 5
 ///
 /// Planting seeds with the help of the Almanac.
 pub fn run(part: &Part) -> Result<usize, Report> {
     // Read in puzzle input
     let input = utils::read_to_string("data/day_5.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 5 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
     let lines = input.split("\n\n").filter(|l| !l.is_empty()).collect_vec();
 
     // Parse seeds as the first source
@@ -20,7 +46,7 @@ pub fn run(part: &Part) -> Result<usize, Report> {
 
     // convert to ranges, varies based on Part 1 or Part 2
     let mut sources = match *part {
-        Part::Part1 => seeds.into_iter().map(|n| n..n).collect_vec(),
+        Part::Part1 => seeds.into_iter().map(|n| n..n + 1).collect_vec(),
         Part::Part2 => seeds.chunks(2).map(|v| v[0]..v[0] + v[1]).collect_vec(),
     };
 
@@ -68,8 +94,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
 
     let result = sources.into_iter().map(|r| r.start).min().unwrap();
 
-    info!("Answer: {result}");
-
     Ok(result)
 }
 
@@ -90,7 +114,7 @@ pub fn split_source(
     // get overlaps between source and target
     let overlaps = target
         .iter()
-        .filter(|t| !(t.end < s.start || t.start > s.end))
+        .filter(|t| t.start < s.end && t.end > s.start)
         .map(|t| max(s.start, t.start)..min(s.end, t.end))
         .collect_vec();
     //debug!("overlaps: {overlaps:?}");
@@ -137,3 +161,26 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// A seed is converted by the first range that contains it, so overlapping
+/// ranges would make the conversion ambiguous.
+fn disjoint_sources(input: &str) -> Result<(), Report> {
+    for section in input.split("\n\n").skip(1) {
+        let mut sources = section
+            .split('\n')
+            .skip(1)
+            .map(|l| l.split(' ').filter_map(|n| n.parse::<usize>().ok()).collect_vec())
+            .map(|m| m[1]..m[1] + m[2])
+            .collect_vec();
+        sources.sort_by_key(|r| r.start);
+        if let Some((a, b)) = sources.iter().tuple_windows().find(|(a, b)| a.end > b.start) {
+            let name = section.split(' ').next().unwrap_or_default();
+            return Err(eyre!("Map {name} has overlapping sources {a:?} and {b:?}."));
+        }
+    }
+    Ok(())
+}
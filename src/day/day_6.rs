@@ -1,14 +1,42 @@
-use crate::{day::Part, utils};
+use crate::{
+    day::{Assumption, Part, Variant},
+    reference, utils,
+};
 
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
 use log::debug;
 use log::info;
 
+/// Day 6 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[Assumption {
+    name: "every record can be beaten",
+    check: beatable,
+}];
+
+/// Day 6 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_6,
+    },
+];
+
 /// Day 6 - Boat Races
 pub fn run(part: &Part) -> Result<usize, Report> {
-    // parse puzzle input into times and distances
     let input = utils::read_to_string("data/day_6.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 6 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
+    // parse puzzle input into times and distances
     let lines = input.split('\n').collect_vec();
 
     let mut times: Vec<usize> = lines[0].split(' ').filter_map(|c| c.parse().ok()).collect();
@@ -59,7 +87,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
 
     let result = result.unwrap_or_default();
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
@@ -78,3 +105,28 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// A race that can't be won still counts as one way to win.
+fn beatable(input: &str) -> Result<(), Report> {
+    let lines = input.split('\n').collect_vec();
+    let times = lines[0].split(' ').filter_map(|c| c.parse::<u128>().ok()).collect_vec();
+    let dists = lines[1].split(' ').filter_map(|c| c.parse::<u128>().ok()).collect_vec();
+
+    // part 2 joins all the races into one
+    let time = times.iter().join("").parse::<u128>()?;
+    let dist = dists.iter().join("").parse::<u128>()?;
+
+    let races = times.into_iter().zip(dists).chain([(time, dist)]);
+    for (t, d) in races {
+        if (t / 2) * (t - t / 2) <= d {
+            return Err(eyre!(
+                "Race with time {t} has a record {d} that can't be beaten."
+            ));
+        }
+    }
+    Ok(())
+}
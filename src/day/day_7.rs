@@ -1,15 +1,41 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
 use crate::poker;
+use crate::reference;
 use crate::utils;
 
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
 use log::info;
 
+/// Day 7 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[Assumption {
+    name: "every hand is different",
+    check: unique_hands,
+}];
+
+/// Day 7 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_7,
+    },
+];
+
 /// Day 7 - Camel Cards (aka poker)
 pub fn run(part: &Part) -> Result<usize, Report> {
     // read in puzzle input
     let input = utils::read_to_string("data/day_7.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 7 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
     let lines = input.split('\n').map(|l| l.split(' ').collect_tuple().unwrap()).collect_vec();
 
     // parse cards and bids into poker hands
@@ -28,7 +54,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
 
     // result is the produce of rank and the bid
     let result = hands.iter().enumerate().map(|(i, h)| h.bid * (hands.len() - i)).sum();
-    info!("Answer: {result}");
     Ok(result)
 }
 
@@ -47,3 +72,16 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// Identical hands would tie, leaving their ranks (and bids) in input order.
+fn unique_hands(input: &str) -> Result<(), Report> {
+    let hands = input.split('\n').map(|l| l.split(' ').next().unwrap_or_default()).collect_vec();
+    match hands.iter().duplicates().next() {
+        Some(hand) => Err(eyre!("Hand {hand} appears more than once.")),
+        None => Ok(()),
+    }
+}
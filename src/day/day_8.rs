@@ -1,21 +1,53 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
+use crate::reference;
 use crate::utils;
 
 use aho_corasick::AhoCorasick;
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
 use log::debug;
 use log::info;
 use prime_factorization::Factorization;
 use std::collections::BTreeMap;
 
+/// Day 8 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[
+    Assumption {
+        name: "nodes AAA and ZZZ exist",
+        check: start_and_end,
+    },
+    Assumption {
+        name: "each ghost reaches Z with a fixed period",
+        check: periodic,
+    },
+];
+
+/// Day 8 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_8,
+    },
+];
+
 /// Day 8 - Ghost Map
-#[allow(clippy::explicit_counter_loop)]
 pub fn run(part: &Part) -> Result<usize, Report> {
-    // read in puzzle input, standardized for easy splitting
     let input = utils::read_to_string("data/day_8.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
+
+/// Solve day 8 from the puzzle input text.
+#[allow(clippy::explicit_counter_loop)]
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
+    // standardize puzzle input for easy splitting
     let ac = AhoCorasick::builder().build(["(", ")", ",", "= "]).unwrap();
-    let input = ac.replace_all(&input, &["", "", "", ""]);
+    let input = ac.replace_all(input, &["", "", "", ""]);
     let lines = input
         .split('\n')
         .filter(|l| !l.is_empty())
@@ -69,7 +101,8 @@ pub fn run(part: &Part) -> Result<usize, Report> {
             .enumerate()
             .map(|(i, n)| {
                 let c = travel.get(n).unwrap()[d];
-                if destination.contains(&c) {
+                // only the first arrival, later ones are multiples of it
+                if destination.contains(&c) && steps[&i] == 0 {
                     steps.insert(i, counter);
                 }
                 c
@@ -112,7 +145,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
     });
     let result = result as usize;
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
@@ -131,3 +163,69 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// Left and right nodes, by node name.
+type Network<'a> = BTreeMap<&'a str, [&'a str; 2]>;
+
+/// Parse the directions and the network of nodes from the puzzle input.
+fn network(input: &str) -> Result<(Vec<usize>, Network), Report> {
+    let (directions, nodes) = input.split_once("\n\n").ok_or_else(|| eyre!("No nodes."))?;
+    let directions = directions.chars().map(|c| (c != 'L') as usize).collect_vec();
+    let network = nodes
+        .split('\n')
+        .map(|l| {
+            let nodes = l.split(|c: char| !c.is_ascii_alphanumeric()).filter(|n| !n.is_empty());
+            match nodes.collect_vec()[..] {
+                [node, left, right] => Ok((node, [left, right])),
+                _ => Err(eyre!("Malformed node: {l}")),
+            }
+        })
+        .collect::<Result<_, Report>>()?;
+    Ok((directions, network))
+}
+
+/// Part 1 walks from AAA to ZZZ.
+fn start_and_end(input: &str) -> Result<(), Report> {
+    let (_, network) = network(input)?;
+    match ["AAA", "ZZZ"].into_iter().find(|n| !network.contains_key(n)) {
+        Some(node) => Err(eyre!("Node {node} does not exist.")),
+        None => Ok(()),
+    }
+}
+
+/// Part 2 takes the LCM of the steps to the first Z node, which only works if
+/// each ghost then returns to that same Z node after the same number of steps.
+fn periodic(input: &str) -> Result<(), Report> {
+    let (directions, network) = network(input)?;
+    let limit = 10_000_000;
+
+    for start in network.keys().filter(|n| n.ends_with('A')) {
+        // steps and node of the first two visits to a Z node
+        let mut node = *start;
+        let mut visits = Vec::new();
+        for (step, d) in directions.iter().cycle().take(limit).enumerate() {
+            node = network.get(node).ok_or_else(|| eyre!("Node {node} does not exist."))?[*d];
+            if node.ends_with('Z') {
+                visits.push((step + 1, node));
+                if visits.len() == 2 {
+                    break;
+                }
+            }
+        }
+
+        match visits[..] {
+            [(first, z1), (second, z2)] if second == 2 * first && z1 == z2 => continue,
+            [(first, z1), (second, z2)] => {
+                return Err(eyre!(
+                    "Ghost from {start} reaches {z1} at step {first}, then {z2} at step {second}."
+                ))
+            }
+            _ => return Err(eyre!("Ghost from {start} does not loop through a Z node.")),
+        }
+    }
+    Ok(())
+}
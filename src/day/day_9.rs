@@ -1,15 +1,40 @@
-use crate::day::Part;
+use crate::day::{Assumption, Part, Variant};
+use crate::reference;
 use crate::utils;
 use crate::utils::table::Table;
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
 use log::debug;
 use log::info;
 
+/// Day 9 assumptions, that the puzzle does not promise.
+pub const ASSUMPTIONS: &[Assumption] = &[Assumption {
+    name: "differences become constant",
+    check: differences_converge,
+}];
+
+/// Day 9 solutions, selectable with `--variant`.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        name: "default",
+        solve,
+    },
+    Variant {
+        name: "reference",
+        solve: reference::day_9,
+    },
+];
+
 /// Day 9 - Oasi
 pub fn run(part: &Part) -> Result<usize, Report> {
     let input = utils::read_to_string("data/day_9.txt")?;
+    let result = solve(&input, part)?;
+    info!("Answer: {result}");
+    Ok(result)
+}
 
+/// Solve day 9 from the puzzle input text.
+pub fn solve(input: &str, part: &Part) -> Result<usize, Report> {
     let extrapolated_values = input
         .split('\n')
         .map(|l| {
@@ -111,7 +136,7 @@ pub fn run(part: &Part) -> Result<usize, Report> {
                         .collect_vec()
                 })
                 .collect_vec();
-            debug!("{l}\n{}", table.to_markdown()?);
+            debug!("{l}\n{}", table.render()?);
 
             // get final extrapolated value
             let final_value = match *part {
@@ -125,7 +150,6 @@ pub fn run(part: &Part) -> Result<usize, Report> {
     // risky conversion!
     let result = extrapolated_values.iter().sum::<isize>() as usize;
 
-    info!("Answer: {result}");
     Ok(result)
 }
 
@@ -144,3 +168,28 @@ fn part_2() -> Result<(), Report> {
     assert_eq!(observed, expected);
     Ok(())
 }
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// Differences are taken until they are all the same, which has to happen
+/// before the sequence runs out of numbers.
+fn differences_converge(input: &str) -> Result<(), Report> {
+    for (i, line) in input.split('\n').enumerate() {
+        let mut row = line.split(' ').map(|n| n.parse::<isize>()).collect::<Result<Vec<_>, _>>()?;
+        loop {
+            row = row.iter().tuple_windows().map(|(a, b)| b - a).collect_vec();
+            if row.is_empty() {
+                return Err(eyre!(
+                    "Sequence on line {} never has constant differences.",
+                    i + 1
+                ));
+            }
+            if row.iter().all_equal() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
@@ -11,10 +11,15 @@ pub mod day_7;
 pub mod day_8;
 pub mod day_9;
 pub mod template;
-use std::str::FromStr;
+use crate::utils;
+use crate::utils::table::Table;
 
 use clap::ValueEnum;
 use color_eyre::eyre::{eyre, Report, Result};
+use itertools::Itertools;
+use log::debug;
+use std::str::FromStr;
+use std::time::Instant;
 
 #[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
 pub enum Part {
@@ -35,3 +40,181 @@ impl FromStr for Part {
         Ok(part)
     }
 }
+
+// ----------------------------------------------------------------------------
+// Variants
+// ----------------------------------------------------------------------------
+
+/// A named solution for a puzzle day.
+#[derive(Clone, Copy)]
+pub struct Variant {
+    pub name: &'static str,
+    pub solve: fn(&str, &Part) -> Result<usize, Report>,
+}
+
+/// Get the solution variants registered by a day, the first is the default.
+pub fn variants(day: &str) -> Result<&'static [Variant], Report> {
+    let variants = match day {
+        "1" => day_1::VARIANTS,
+        "2" => day_2::VARIANTS,
+        "3" => day_3::VARIANTS,
+        "4" => day_4::VARIANTS,
+        "5" => day_5::VARIANTS,
+        "6" => day_6::VARIANTS,
+        "7" => day_7::VARIANTS,
+        "8" => day_8::VARIANTS,
+        "9" => day_9::VARIANTS,
+        "10" => day_10::VARIANTS,
+        "11" => day_11::VARIANTS,
+        "12" => day_12::VARIANTS,
+        _ => return Err(eyre!("Day {day} is not implemented yet.")),
+    };
+
+    Ok(variants)
+}
+
+/// Get a solution variant of a day by name, or the default variant.
+pub fn variant(day: &str, name: Option<&str>) -> Result<Variant, Report> {
+    let variants = variants(day)?;
+    let variant = match name {
+        Some(name) => variants.iter().find(|v| v.name == name).ok_or_else(|| {
+            let names = variants.iter().map(|v| v.name).join(", ");
+            eyre!("Day {day} has no variant {name:?}, choose from: {names}")
+        })?,
+        None => &variants[0],
+    };
+
+    Ok(*variant)
+}
+
+/// Solve a puzzle day and part from the puzzle input text, with the default variant.
+pub fn solve(day: &str, input: &str, part: &Part) -> Result<usize, Report> {
+    let variant = variant(day, None)?;
+    (variant.solve)(input, part)
+}
+
+/// Run every variant of a day on the same input.
+///
+/// Returns a table of each variant's answer and run time, and whether all
+/// the variants agree with the default variant.
+pub fn compare(day: &str, input: &str, parts: &[Part]) -> Result<(Table, bool), Report> {
+    let mut table = Table::new();
+    table.headers = ["part", "variant", "answer", "time", "agrees"].map(String::from).to_vec();
+    let mut agree = true;
+
+    for part in parts {
+        let mut default = None;
+        for variant in variants(day)? {
+            let start = Instant::now();
+            let answer = utils::catch_panic(|| (variant.solve)(input, part));
+            let time = start.elapsed();
+            debug!(
+                "Day {day} {part:?} {}: {answer:?} in {time:?}",
+                variant.name
+            );
+
+            let answer = answer.map_err(|e| e.to_string());
+            let agrees = default.get_or_insert_with(|| answer.clone()) == &answer && answer.is_ok();
+            agree &= agrees;
+
+            let answer = match answer {
+                Ok(answer) => answer.to_string(),
+                Err(e) => format!("error ({e})"),
+            };
+            table.rows.push(vec![
+                format!("{part:?}"),
+                variant.name.to_string(),
+                answer,
+                format!("{time:?}"),
+                if agrees { "yes" } else { "no" }.to_string(),
+            ]);
+        }
+    }
+
+    Ok((table, agree))
+}
+
+// ----------------------------------------------------------------------------
+// Assumptions
+// ----------------------------------------------------------------------------
+
+/// A premise that a solution relies on, but the puzzle does not promise.
+///
+/// The check returns an error explaining how the input violates it.
+#[derive(Clone, Copy)]
+pub struct Assumption {
+    pub name: &'static str,
+    pub check: fn(&str) -> Result<(), Report>,
+}
+
+/// Get the assumptions declared by a day's solutions.
+pub fn assumptions(day: &str) -> Result<&'static [Assumption], Report> {
+    let assumptions = match day {
+        "1" => day_1::ASSUMPTIONS,
+        "2" => day_2::ASSUMPTIONS,
+        "3" => day_3::ASSUMPTIONS,
+        "4" => day_4::ASSUMPTIONS,
+        "5" => day_5::ASSUMPTIONS,
+        "6" => day_6::ASSUMPTIONS,
+        "7" => day_7::ASSUMPTIONS,
+        "8" => day_8::ASSUMPTIONS,
+        "9" => day_9::ASSUMPTIONS,
+        "10" => day_10::ASSUMPTIONS,
+        "11" => day_11::ASSUMPTIONS,
+        "12" => day_12::ASSUMPTIONS,
+        _ => return Err(eyre!("Day {day} is not implemented yet.")),
+    };
+
+    Ok(assumptions)
+}
+
+/// Check every assumption of a day against an input.
+///
+/// Returns a table of each assumption and how it was violated, and whether
+/// all the assumptions hold.
+pub fn lint(day: &str, input: &str) -> Result<(Table, bool), Report> {
+    let mut table = Table::new();
+    table.headers = ["assumption", "holds", "details"].map(String::from).to_vec();
+    let mut hold = true;
+
+    for assumption in assumptions(day)? {
+        let result = utils::catch_panic(|| (assumption.check)(input));
+        debug!("Day {day} {}: {result:?}", assumption.name);
+        hold &= result.is_ok();
+
+        let (holds, details) = match result {
+            Ok(()) => ("yes", String::new()),
+            Err(e) => ("no", e.to_string()),
+        };
+        table.rows.push(vec![
+            assumption.name.to_string(),
+            holds.to_string(),
+            details,
+        ]);
+    }
+
+    Ok((table, hold))
+}
+
+#[test]
+fn compare_variants() -> Result<(), Report> {
+    let input = crate::generate::generate("4", 10, 0)?;
+    let (table, agree) = compare("4", &input, &[Part::Part1, Part::Part2])?;
+    assert!(agree);
+    assert_eq!(table.rows.len(), 2 * variants("4")?.len());
+    Ok(())
+}
+
+#[test]
+fn lint_assumptions() -> Result<(), Report> {
+    let input = crate::generate::generate("4", 10, 0)?;
+    let (_table, hold) = lint("4", &input)?;
+    assert!(hold);
+
+    // card ids no longer start at 1
+    let input = input.split_once('\n').unwrap().1;
+    let (table, hold) = lint("4", input)?;
+    assert!(!hold);
+    assert_eq!(table.rows[0][1], "no");
+    Ok(())
+}
@@ -0,0 +1,230 @@
+use crate::game::{Map, Neighborhood, Point};
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// ----------------------------------------------------------------------------
+// Cycle detection
+// ----------------------------------------------------------------------------
+
+/// A repeating sequence of states, the step a repeated state was first seen
+/// and how many steps it takes to come back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        match n < self.start {
+            true => n,
+            false => self.start + (n - self.start) % self.period,
+        }
+    }
+}
+
+/// Step from the start up to `n` times, stopping early at the first repeated
+/// state. Returns every state seen in order, and the cycle if one was found.
+fn history<S: Clone + Eq + Hash>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+    n: usize,
+) -> (Vec<S>, Option<Cycle>) {
+    let mut seen = HashMap::from([(start.clone(), 0)]);
+    let mut states = vec![start];
+
+    for i in 1..=n {
+        let state = step(&states[i - 1]);
+        if let Some(first) = seen.get(&state) {
+            let cycle = Cycle {
+                start: *first,
+                period: i - first,
+            };
+            return (states, Some(cycle));
+        }
+        seen.insert(state.clone(), i);
+        states.push(state);
+    }
+
+    (states, None)
+}
+
+/// Find the first state that repeats within a number of steps, and its cycle.
+pub fn find_cycle<S: Clone + Eq + Hash>(
+    start: S,
+    step: impl FnMut(&S) -> S,
+    max_steps: usize,
+) -> Option<(S, Cycle)> {
+    let (mut states, cycle) = history(start, step, max_steps);
+    cycle.map(|c| (states.swap_remove(c.start), c))
+}
+
+/// The state after `n` steps, skipping ahead once the states start repeating.
+pub fn nth_state<S: Clone + Eq + Hash>(start: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (mut states, cycle) = history(start, step, n);
+    let i = cycle.map_or(n, |c| c.equivalent(n));
+    states.swap_remove(i)
+}
+
+// ----------------------------------------------------------------------------
+// Cellular automaton
+// ----------------------------------------------------------------------------
+
+/// Steps a map by applying a rule to every tile at once.
+///
+/// The rule gets each tile and its neighbors, and returns the tile's next
+/// value. Neighbors are the [`Neighborhood::MOORE`] by default. The next map
+/// is written into a second buffer, which is swapped in afterwards, so the map
+/// isn't reallocated every step.
+#[derive(Clone)]
+pub struct Automaton<R: Fn(char, &[char]) -> char> {
+    pub map: Map,
+    next: Map,
+    rule: R,
+    neighborhood: Neighborhood,
+    /// Number of steps taken so far.
+    pub steps: usize,
+}
+
+impl<R: Fn(char, &[char]) -> char> Automaton<R> {
+    pub fn new(map: Map, rule: R) -> Self {
+        Automaton {
+            next: map.clone(),
+            map,
+            rule,
+            neighborhood: Neighborhood::MOORE,
+            steps: 0,
+        }
+    }
+
+    /// Use a different neighborhood for the rule.
+    pub fn with_neighborhood(self, neighborhood: Neighborhood) -> Self {
+        Automaton {
+            neighborhood,
+            ..self
+        }
+    }
+
+    /// Take one step, returning whether any tile changed.
+    pub fn step(&mut self) -> bool {
+        let mut changed = false;
+        let mut neighbors = Vec::with_capacity(self.neighborhood.offsets.len());
+
+        for y in 0..self.map.rows() {
+            for x in 0..self.map.columns() {
+                let p = Point::new(x, y);
+                neighbors.clear();
+                let n = self.map.neighbors(p, &self.neighborhood);
+                neighbors.extend(n.map(|n| self.map.tiles[n]));
+                let tile = (self.rule)(self.map.tiles[p], &neighbors);
+                changed |= tile != self.map.tiles[p];
+                self.next.tiles[p] = tile;
+            }
+        }
+
+        std::mem::swap(&mut self.map, &mut self.next);
+        self.steps += 1;
+        changed
+    }
+
+    /// Step until nothing changes, returning the number of steps taken, or
+    /// `None` if it hasn't settled after a number of steps.
+    pub fn run_until_stable(&mut self, max_steps: usize) -> Option<usize> {
+        let start = self.steps;
+        for _ in 0..max_steps {
+            if !self.step() {
+                return Some(self.steps - start);
+            }
+        }
+        None
+    }
+
+    /// Step forward a number of times, skipping ahead once the maps start
+    /// repeating. Returns the first repeated map and its cycle, if one was
+    /// found.
+    pub fn run(&mut self, n: usize) -> Option<(Map, Cycle)> {
+        let steps = self.steps + n;
+        let (mut maps, cycle) = history(self.map.clone(), |_| self.advance(), n);
+        let repeated = cycle.map(|c| (maps[c.start].clone(), c));
+        let i = cycle.map_or(n, |c| c.equivalent(n));
+        self.map = maps.swap_remove(i);
+        self.steps = steps;
+        repeated
+    }
+
+    /// Take one step, and return a copy of the new map.
+    fn advance(&mut self) -> Map {
+        self.step();
+        self.map.clone()
+    }
+}
+
+#[test]
+fn automaton() -> Result<(), color_eyre::eyre::Report> {
+    use crate::game::Direction;
+    use std::str::FromStr;
+
+    // game of life, a blinker flips every step and a block never changes
+    let life = |c: char, n: &[char]| match (c, n.iter().filter(|t| **t == '#').count()) {
+        ('#', 2 | 3) | ('.', 3) => '#',
+        _ => '.',
+    };
+    // a lead in of 3 steps, then a cycle through 3, 4 and 5
+    let cycle = Cycle {
+        start: 3,
+        period: 3,
+    };
+    let step = |n: &usize| if *n < 5 { n + 1 } else { 3 };
+    assert_eq!(find_cycle(0, step, 10), Some((3, cycle)));
+    assert_eq!(find_cycle(0, step, 5), None);
+
+    let blinker = Map::from_str(".....\n..#..\n..#..\n..#..\n.....")?;
+    let period = |cycle: Option<(Map, Cycle)>| cycle.map(|(m, c)| (m.tiles, c.start, c.period));
+    assert_eq!(
+        period(find_cycle(blinker.clone(), |m| m.clone(), 1)),
+        Some((blinker.tiles.clone(), 0, 1))
+    );
+    let mut automaton = Automaton::new(blinker.clone(), life);
+    assert!(automaton.step());
+    assert_eq!(
+        automaton.map.tiles.to_string(),
+        ".....\n.....\n.###.\n.....\n....."
+    );
+    let flipped = automaton.map.tiles.clone();
+    assert_eq!(period(automaton.run(1001)), Some((flipped, 0, 2)));
+    assert_eq!(automaton.steps, 1002);
+    assert_eq!(automaton.map.tiles, blinker.tiles);
+    assert_eq!(automaton.run_until_stable(10), None);
+
+    let mut block = Automaton::new(Map::from_str("....\n.##.\n.##.\n....")?, life);
+    assert_eq!(block.run_until_stable(10), Some(1));
+
+    // a glider on a torus comes back to where it started
+    let glider = Map::from_str(".#....\n..#...\n###...\n......\n......\n......")?;
+    let torus = Neighborhood::MOORE.wrapping();
+    let mut torus = Automaton::new(glider.clone(), life).with_neighborhood(torus);
+    assert_eq!(period(torus.run(100)), Some((glider.tiles, 0, 24)));
+
+    // 2023 day 14, each cycle tilts north, west, south and east
+    let dish = Map::from_str(
+        "O....#....\nO.OO#....#\n.....##...\nOO.#O....O\n.O.....O#.\n\
+         O.#..O.#.#\n..O..#O..O\n.......O..\n#....###..\n#OO..#....",
+    )?;
+    let spin = |m: &Map| {
+        let mut m = m.clone();
+        let directions = [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ];
+        directions.into_iter().for_each(|d| m.tilt('O', '.', d));
+        m
+    };
+    let dish = nth_state(dish, spin, 1_000_000_000);
+    let load = dish.search(&'O').into_iter().map(|p| dish.rows() - p.y);
+    assert_eq!(load.sum::<usize>(), 64);
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use crate::game::Point;
+
+use color_eyre::eyre::{eyre, Report, Result};
+use itertools::Itertools;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// A rectangular grid of cells, stored row by row in one contiguous vector.
+///
+/// Cells are addressed by `(x, y)`, where `x` is the column and `y` is the row.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Build a grid from rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, Report> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        let height = rows.len();
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            return Err(eyre!(
+                "Row {y} has {} cells, but the first row has {width}.",
+                rows[y].len()
+            ));
+        }
+
+        let cells = rows.into_iter().flatten().collect_vec();
+        Ok(Grid {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Build a grid by calling a function for each `(x, y)` coordinate.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y)));
+        let cells = cells.map(|(x, y)| f(x, y)).collect_vec();
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Check if a coordinate is inside the grid.
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Get a cell, or `None` if the coordinate is outside the grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.contains(x, y).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// Get a mutable cell, or `None` if the coordinate is outside the grid.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        match self.contains(x, y) {
+            true => Some(&mut self.cells[y * self.width + x]),
+            false => None,
+        }
+    }
+
+    /// Get a row of cells, left to right.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Iterate over the rows, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a zero width
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// Iterate over a column of cells, top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    /// Iterate over the columns, left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterate over the cells and their coordinates, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, c)| (Point::new(i % width, i / width), c))
+    }
+
+    /// Get the coordinates of every cell that matches a predicate, row by row.
+    pub fn positions(&self, predicate: impl Fn(&T) -> bool) -> Vec<Point> {
+        self.iter().filter(|(_, c)| predicate(c)).map(|(p, _)| p).collect_vec()
+    }
+
+    /// Convert every cell into another type.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect_vec(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Build a grid with every cell set to the same value.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Swap rows and columns, mirroring across the main diagonal.
+    pub fn transpose(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// Rotate clockwise by a number of quarter turns (90 degrees each).
+    pub fn rotate(&self, quarter_turns: usize) -> Self {
+        let (w, h) = (self.width, self.height);
+        match quarter_turns % 4 {
+            1 => Grid::from_fn(h, w, |x, y| self[(y, h - 1 - x)].clone()),
+            2 => Grid::from_fn(w, h, |x, y| self[(w - 1 - x, h - 1 - y)].clone()),
+            3 => Grid::from_fn(h, w, |x, y| self[(w - 1 - y, x)].clone()),
+            _ => self.clone(),
+        }
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(x, self.height - 1 - y)].clone()
+        })
+    }
+
+    /// Copy the cells out into rows.
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        self.rows().map(|row| row.to_vec()).collect_vec()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({x}, {y}) is outside the {}x{} grid.",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside the {width}x{height} grid."))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        &self[(p.x, p.y)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        &mut self[(p.x, p.y)]
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Report;
+
+    fn from_str(input: &str) -> Result<Self, Report> {
+        let rows = input.lines().map(|l| l.chars().collect_vec()).collect_vec();
+        Grid::from_rows(rows)
+    }
+}
+
+impl std::fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = self.rows().map(|row| row.iter().collect::<String>());
+        write!(f, "{}", rows.format("\n"))
+    }
+}
+
+#[test]
+fn grid() -> Result<(), Report> {
+    let mut grid = Grid::from_str("abc\ndef")?;
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.get(2, 1), Some(&'f'));
+    assert_eq!(grid.get(3, 0), None);
+    assert_eq!(grid.row(1), ['d', 'e', 'f']);
+    assert_eq!(grid.column(1).collect::<String>(), "be");
+    let positions = grid.positions(|c| *c > 'c');
+    assert_eq!(positions, [(0, 1), (1, 1), (2, 1)].map(Point::from));
+
+    grid[(0, 0)] = 'z';
+    let upper = grid.map(|c| c.to_ascii_uppercase());
+    assert_eq!(upper.to_string(), "ZBC\nDEF");
+
+    let error = Grid::from_str("abc\nde").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Row 1 has 2 cells, but the first row has 3."
+    );
+    Ok(())
+}
+
+#[test]
+fn transforms() -> Result<(), Report> {
+    let grid = Grid::from_str("abc\ndef")?;
+    assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+    assert_eq!(grid.rotate(1).to_string(), "da\neb\nfc");
+    assert_eq!(grid.rotate(2).to_string(), "fed\ncba");
+    assert_eq!(grid.rotate(3).to_string(), "cf\nbe\nad");
+    assert_eq!(grid.rotate(4), grid);
+    assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+    assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+    Ok(())
+}
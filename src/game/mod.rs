@@ -1,12 +1,36 @@
-use crate::utils::table::Table;
-
-use color_eyre::eyre::{Report, Result};
+pub mod automaton;
+pub mod grid;
+pub mod neighborhood;
+pub mod pattern;
+pub mod pipe;
+pub mod point;
+pub mod polygon;
+pub mod render;
+pub mod search;
+pub mod sparse;
+pub mod tiled;
+
+pub use automaton::{find_cycle, nth_state, Automaton, Cycle};
+pub use grid::Grid;
+pub use neighborhood::Neighborhood;
+pub use pattern::{Orientation, Pattern, PatternMatch, StrMatch};
+pub use pipe::Pipe;
+pub use point::{Direction, Point};
+pub use render::{Color, Overlay, Renderer, Style, TileStyle, Viewport};
+pub use search::{astar, bfs, dijkstra, Heading, Search};
+pub use sparse::{Bounds, Compression, Coord, SparseMap};
+pub use tiled::TiledMap;
+
+use crate::utils::table::{self, Format, Layout, Table};
+
+use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
+use std::collections::VecDeque;
 use std::str::FromStr;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Map {
-    pub tiles: Vec<Vec<char>>,
+    pub tiles: Grid<char>,
 }
 
 impl Default for Map {
@@ -17,26 +41,32 @@ impl Default for Map {
 
 impl Map {
     pub fn new() -> Self {
-        Map { tiles: Vec::new() }
+        Map {
+            tiles: Grid::default(),
+        }
     }
 
     pub fn rows(&self) -> usize {
-        self.tiles.len()
+        self.tiles.height()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.tiles.width()
     }
 
     /// Check if a map coordinate is a character. If so return the full
     /// number and the coordinate range it spans.
-    pub fn get_character(&self, x: usize, y: usize) -> Option<(usize, Vec<(usize, usize)>)> {
+    pub fn get_character(&self, p: Point) -> Option<(usize, Vec<Point>)> {
         // check if the coordinate is a digit
-        if !self.tiles[y][x].is_ascii_digit() {
+        if !self.tiles[p].is_ascii_digit() {
             return None;
         }
 
-        let mut start = x;
+        let mut start = p.x;
 
         // walk backwards to find start coordinate
-        for x_i in (0..x).rev() {
-            match self.tiles[y][x_i].is_ascii_digit() {
+        for x_i in (0..p.x).rev() {
+            match self.tiles[(x_i, p.y)].is_ascii_digit() {
                 true => start = x_i,
                 false => break,
             }
@@ -45,8 +75,8 @@ impl Map {
         // read left to right from start
         let mut digits = Vec::new();
 
-        for x_i in start..self.tiles[y].len() {
-            let c = self.tiles[y][x_i];
+        for x_i in start..self.tiles.width() {
+            let c = self.tiles[(x_i, p.y)];
             match c.is_ascii_digit() {
                 true => digits.push(c),
                 false => break,
@@ -54,53 +84,36 @@ impl Map {
         }
 
         // get coordinate range of digit
-        let coordinates = (start..(start + digits.len())).map(|x| (x, y)).collect();
+        let coordinates = (start..(start + digits.len())).map(|x| Point::new(x, p.y)).collect();
 
         // parse digits into number
         let part_number: usize = digits.into_iter().join("").parse().unwrap();
         Some((part_number, coordinates))
     }
 
-    pub fn distance(&self, t1: (usize, usize), t2: (usize, usize)) -> usize {
-        ((t2.0 as isize - t1.0 as isize).abs() + (t2.1 as isize - t1.1 as isize).abs()) as usize
+    pub fn distance(&self, t1: Point, t2: Point) -> usize {
+        t1.manhattan(&t2)
     }
 
-    /// Make this not single character
-    pub fn search(&self, query: &char) -> Vec<(usize, usize)> {
-        self.tiles
-            .iter()
-            .enumerate()
-            .filter(|(_y, row)| row.contains(query))
-            .flat_map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter_map(|(x, c)| (c == query).then_some((x, y)))
-                    .collect_vec()
-            })
-            .collect_vec()
+    /// Find every tile that is a character, see [`Map::search_str`] for strings.
+    pub fn search(&self, query: &char) -> Vec<Point> {
+        self.tiles.positions(|c| c == query)
     }
 
-    pub fn find_tile(&self, tile: &Tile) -> Vec<Vec<(usize, usize)>> {
+    pub fn find_tile(&self, tile: &Tile) -> Vec<Vec<Point>> {
         let coordinates = self
             .tiles
             .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter_map(|(x, c)| {
-                        let coords = vec![(x, y)];
-                        match *tile {
-                            Tile::Character => self.get_character(x, y).map(|(_, coords)| coords),
-                            Tile::Enemy => (!c.is_ascii_digit()
-                                && c.to_string() != Tile::Base.to_string())
-                            .then_some(coords),
-                            Tile::Base | Tile::Trap => {
-                                (c.to_string() == tile.to_string()).then_some(coords)
-                            }
-                        }
-                    })
-                    .collect_vec()
+            .filter_map(|(p, c)| {
+                let coords = vec![p];
+                match *tile {
+                    Tile::Character => self.get_character(p).map(|(_, coords)| coords),
+                    Tile::Enemy => (!c.is_ascii_digit() && c.to_string() != Tile::Base.to_string())
+                        .then_some(coords),
+                    Tile::Base | Tile::Trap => {
+                        (c.to_string() == tile.to_string()).then_some(coords)
+                    }
+                }
             })
             .unique()
             .collect_vec();
@@ -108,134 +121,217 @@ impl Map {
         coordinates
     }
 
+    /// Get the neighbor in a direction, if it's on the map.
+    pub fn step(&self, p: Point, direction: Direction) -> Option<Point> {
+        p.step(direction).filter(|n| self.is_tile(*n))
+    }
+
     // Get coordinates of all neighboring cells.
-    pub fn get_neighbors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        let (x, y) = (x as isize, y as isize);
-        (x - 1..=x + 1)
-            .flat_map(|x| (y - 1..=y + 1).map(|y| (x, y)).collect_vec())
-            .filter(|(x_i, y_i)| (*x_i != x || *y_i != y))
-            .filter(|(x, y)| self.is_tile(*x, *y))
-            .map(|(x, y)| (x as usize, y as usize))
-            .collect_vec()
+    pub fn get_neighbors(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.iter().filter_map(move |d| self.step(p, *d))
     }
 
     // Get neighbors cells in cross pattern, no diagnoal
-    pub fn get_simple_neighbors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        let (x, y) = (x as isize, y as isize);
-        vec![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
-            .into_iter()
-            .filter(|(x, y)| self.is_tile(*x, *y))
-            .map(|(x, y)| (x as usize, y as usize))
-            .collect_vec()
+    pub fn get_simple_neighbors(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::CARDINAL.iter().filter_map(move |d| self.step(p, *d))
     }
 
     // Get neighbors based on a pipe
-    pub fn get_pipe_neighbors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        let c = self.tiles[y][x];
-        let (x, y) = (x as i32, y as i32);
-
-        let n: Vec<(i32, i32)> = match c {
-            'S' | '+' => vec![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)],
-            '|' => vec![(x, y - 1), (x, y + 1)],
-            '-' => vec![(x - 1, y), (x + 1, y)],
-            'L' => vec![(x, y - 1), (x + 1, y)],
-            'J' => vec![(x - 1, y), (x, y - 1)],
-            '7' => vec![(x - 1, y), (x, y + 1)],
-            'F' => vec![(x, y + 1), (x + 1, y)],
-            _ => vec![],
-        };
+    pub fn get_pipe_neighbors(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        let directions = self.pipe(p).map(|pipe| pipe.connections()).unwrap_or_default();
 
-        // filter to valid coord
-        n.into_iter()
-            // filter to valid coordinates
-            .filter_map(|(x, y)| (x >= 0 && y >= 0).then_some((x as usize, y as usize)))
-            .filter(|(_x, y)| *y < self.tiles.len())
-            .filter(|(x, y)| *x < self.tiles[*y].len())
-            .collect_vec()
+        // filter to valid coordinates
+        directions.iter().filter_map(move |d| self.step(p, *d))
     }
 
-    // check if coordiantes are in the tile map boundary
-    pub fn is_tile(&self, x: isize, y: isize) -> bool {
-        match x >= 0 && y >= 0 {
-            true => {
-                let (x, y) = (x as usize, y as usize);
-                match y < self.tiles.len() {
-                    true => x < self.tiles[y].len(),
-                    false => false,
+    /// Follow the pipe loop through the start, returning its tiles in order.
+    ///
+    /// The start is first, and the pipe under the start is inferred from the
+    /// two neighbors that connect back to it, see [`Map::infer_start`].
+    pub fn trace_loop(&self, start: Point) -> Result<(Vec<Point>, Pipe), Report> {
+        let pipe = self.infer_start(start)?;
+        let first = pipe.connections()[0];
+
+        let mut path = vec![start];
+        let (mut previous, mut current) = (start, self.step(start, first).unwrap());
+        while current != start {
+            // a second start or a cross connects everywhere, and can lead
+            // into a cycle that never comes back
+            if path.len() >= self.rows() * self.columns() {
+                return Err(eyre!("Pipe loop doesn't return to the start."));
+            }
+            path.push(current);
+            let next = self.get_pipe_neighbors(current).find(|n| *n != previous);
+            let next = next.ok_or_else(|| eyre!("Pipe loop is broken at {current}."))?;
+            if !self.get_pipe_neighbors(next).any(|n| n == current) {
+                return Err(eyre!("Pipe loop is broken between {current} and {next}."));
+            }
+            (previous, current) = (current, next);
+        }
+
+        Ok((path, pipe))
+    }
+
+    /// Classify every tile as inside, outside or on the pipe loop through the start.
+    ///
+    /// Walks each row left to right, flipping between outside and inside each
+    /// time the loop is crossed. A `|` is a crossing, and so are the bends
+    /// `L-..-7` and `F-..-J`, while `L-..-J` and `F-..-7` only touch the loop.
+    pub fn scanline(&self, start: Point) -> Result<Grid<Region>, Report> {
+        let (pipe_loop, start_pipe) = self.trace_loop(start)?;
+        let mut regions = Grid::new(self.columns(), self.rows(), Region::Outside);
+        pipe_loop.iter().for_each(|p| regions[*p] = Region::Loop);
+
+        for y in 0..self.rows() {
+            let (mut inside, mut bend) = (false, None);
+            for x in 0..self.columns() {
+                if regions[(x, y)] != Region::Loop {
+                    if inside {
+                        regions[(x, y)] = Region::Inside;
+                    }
+                    continue;
+                }
+                let pipe = match self.pipe(Point::new(x, y)) {
+                    Some(Pipe::Start) => Some(start_pipe),
+                    pipe => pipe,
+                };
+                match (pipe, bend) {
+                    (Some(Pipe::Vertical), _) => inside = !inside,
+                    (Some(Pipe::NorthEast | Pipe::SouthEast), _) => bend = pipe,
+                    (Some(Pipe::SouthWest), Some(Pipe::NorthEast))
+                    | (Some(Pipe::NorthWest), Some(Pipe::SouthEast)) => inside = !inside,
+                    _ => (),
                 }
             }
-            false => false,
         }
+
+        Ok(regions)
+    }
+
+    // check if coordiantes are in the tile map boundary
+    pub fn is_tile(&self, p: Point) -> bool {
+        self.tiles.contains(p.x, p.y)
     }
 
     /// flood fill algorithm, but following pipes
-    pub fn flood_fill(&self, x: usize, y: usize, follow_pipes: bool) -> Vec<(usize, usize)> {
+    ///
+    /// Returns every tile reached from the start (including the start), in
+    /// breadth first order. Visited tiles are tracked in a grid of flags, so
+    /// each tile is only checked once.
+    pub fn flood_fill(&self, start: Point, follow_pipes: bool) -> Vec<Point> {
         let mut f = Vec::new();
 
         // if not following pipes, we can only fill ground ('.')
-        if !follow_pipes && self.tiles[y][x] != '.' {
+        if !follow_pipes && self.tiles[start] != '.' {
             return f;
         }
-        let mut current = vec![(x, y)];
-
-        while !current.is_empty() {
-            current = current
-                .into_iter()
-                .flat_map(|(x, y)| {
-                    let mut next_nodes = match follow_pipes {
-                        true => self
-                            .get_pipe_neighbors(x, y)
-                            .into_iter()
-                            .filter(|(xn, yn)| self.get_pipe_neighbors(*xn, *yn).contains(&(x, y)))
-                            .collect_vec(),
-                        false => self
-                            .get_simple_neighbors(x, y)
-                            .into_iter()
-                            .filter(|(x, y)| self.tiles[*y][*x] == '.')
-                            .collect_vec(),
-                    };
-
-                    // don't backtrack
-                    next_nodes.retain(|(x, y)| !f.contains(&(*x, *y)));
-
-                    f.extend(next_nodes.clone());
-                    next_nodes
-                })
-                .collect_vec();
+
+        let mut visited = Grid::new(self.columns(), self.rows(), false);
+        let mut queue = VecDeque::from([start]);
+        visited[start] = true;
+
+        while let Some(p) = queue.pop_front() {
+            f.push(p);
+            // don't backtrack
+            let visit = |n: Point| {
+                if !visited[n] {
+                    visited[n] = true;
+                    queue.push_back(n);
+                }
+            };
+            match follow_pipes {
+                true => Direction::CARDINAL
+                    .into_iter()
+                    .filter(|d| self.connected(p, *d))
+                    .filter_map(|d| self.step(p, d))
+                    .for_each(visit),
+                false => {
+                    self.get_simple_neighbors(p).filter(|n| self.tiles[*n] == '.').for_each(visit)
+                }
+            }
         }
 
         f
     }
 
-    // /// flood fill algorithm
-    // pub fn flood_fill(
-    //     &self,
-    //     x: usize,
-    //     y: usize,
-    //     mut f: Vec<(usize, usize)>,
-    // ) -> Vec<(usize, usize)> {
-    //     f.push((x, y));
-
-    //     let next_nodes = self
-    //         .get_pipe_neighbors(x, y)
-    //         .into_iter()
-    //         // don't backtrack
-    //         .filter(|(xn, yn)| !f.contains(&(*xn, *yn)))
-    //         // don't enter start node
-    //         .filter(|(xn, yn)| self.tiles[*yn][*xn] != 'S')
-    //         // make sure pipe goes both ways
-    //         .filter(|(xn, yn)| self.get_pipe_neighbors(*xn, *yn).contains(&(x, y)))
-    //         .collect_vec();
-
-    //     for (x, y) in next_nodes {
-    //         if f.contains(&(x, y)) {
-    //             continue;
-    //         }
-    //         f = self.flood_fill(x, y, f.clone());
-    //     }
-
-    //     f
-    // }
+    /// Label the connected regions of tiles that match a predicate, in one pass.
+    ///
+    /// Regions connect in the four cardinal directions, and are numbered in the
+    /// order their first tile appears, row by row.
+    pub fn components(&self, predicate: impl Fn(&char) -> bool) -> Components {
+        let mut components = Components {
+            labels: Grid::new(self.columns(), self.rows(), None),
+            sizes: Vec::new(),
+            touches_border: Vec::new(),
+        };
+        let on_border =
+            |p: Point| p.x == 0 || p.y == 0 || p.x + 1 == self.columns() || p.y + 1 == self.rows();
+
+        let mut queue = VecDeque::new();
+        for (start, c) in self.tiles.iter() {
+            if !predicate(c) || components.labels[start].is_some() {
+                continue;
+            }
+
+            let label = components.sizes.len();
+            let (mut size, mut border) = (0, false);
+            components.labels[start] = Some(label);
+            queue.push_back(start);
+
+            while let Some(p) = queue.pop_front() {
+                size += 1;
+                border |= on_border(p);
+                for n in self.get_simple_neighbors(p) {
+                    if predicate(&self.tiles[n]) && components.labels[n].is_none() {
+                        components.labels[n] = Some(label);
+                        queue.push_back(n);
+                    }
+                }
+            }
+
+            components.sizes.push(size);
+            components.touches_border.push(border);
+        }
+
+        components
+    }
+
+    /// Rotate the map clockwise by a number of quarter turns (90 degrees each).
+    pub fn rotate(&mut self, quarter_turns: usize) {
+        self.tiles = self.tiles.rotate(quarter_turns);
+    }
+
+    /// Swap the rows and columns of the map.
+    pub fn transpose(&mut self) {
+        self.tiles = self.tiles.transpose();
+    }
+
+    /// Mirror the map left to right.
+    pub fn flip_horizontal(&mut self) {
+        self.tiles = self.tiles.flip_horizontal();
+    }
+
+    /// Mirror the map top to bottom.
+    pub fn flip_vertical(&mut self) {
+        self.tiles = self.tiles.flip_vertical();
+    }
+
+    /// Slide every `tile` in a direction, over `empty` tiles, until it's blocked
+    /// by the edge of the map or another tile.
+    pub fn tilt(&mut self, tile: char, empty: char, direction: Direction) {
+        // move the tiles furthest along the direction first, so they make room
+        let (dx, dy) = direction.offset();
+        let mut tiles = self.search(&tile);
+        tiles.sort_by_key(|p| -(p.x as isize * dx + p.y as isize * dy));
+
+        for mut p in tiles {
+            while let Some(n) = self.step(p, direction).filter(|n| self.tiles[*n] == empty) {
+                self.tiles[n] = tile;
+                self.tiles[p] = empty;
+                p = n;
+            }
+        }
+    }
 
     pub fn zoom_in(&mut self) {
         self.push_pipe_columns();
@@ -243,73 +339,49 @@ impl Map {
     }
 
     pub fn zoom_out(&mut self) {
-        self.tiles = self
-            .tiles
-            .clone()
-            .into_iter()
-            .enumerate()
-            .filter_map(|(y, row)| (y % 2 == 0).then_some(row))
-            .map(|row| row.into_iter().step_by(2).collect_vec())
-            .collect_vec();
+        let (width, height) = (self.columns().div_ceil(2), self.rows().div_ceil(2));
+        self.tiles = Grid::from_fn(width, height, |x, y| self.tiles[(x * 2, y * 2)]);
     }
 
     /// push apart each pipe row
     pub fn push_pipe_rows(&mut self) {
-        self.tiles = self
-            .tiles
-            .iter()
-            .enumerate()
-            .flat_map(|(y, row)| match y == self.tiles.len() - 1 {
-                true => vec![row.clone()],
-                false => {
-                    let new_row = (0..row.len())
-                        .map(|x| {
-                            let n1 = self.get_pipe_neighbors(x, y);
-                            let n2 = self.get_pipe_neighbors(x, y + 1);
-                            match n1.contains(&(x, y + 1)) && n2.contains(&(x, y)) {
-                                true => '|',
-                                false => '*',
-                            }
-                        })
-                        .collect_vec();
-                    vec![row.clone(), new_row]
+        let height = (self.rows() * 2).saturating_sub(1);
+        self.tiles = Grid::from_fn(self.columns(), height, |x, y| match y % 2 == 0 {
+            true => self.tiles[(x, y / 2)],
+            false => {
+                let y = y / 2;
+                let p1 = Point::new(x, y);
+                match self.connected(p1, Direction::South) {
+                    true => char::from(Pipe::Vertical),
+                    false => Pipe::GAP,
                 }
-            })
-            .collect_vec();
+            }
+        });
     }
 
     /// push apart each pipe column
     pub fn push_pipe_columns(&mut self) {
-        self.tiles = (0..self.tiles.len())
-            .map(|y| {
-                (0..self.tiles[y].len())
-                    .flat_map(|x| {
-                        let c = self.tiles[y][x];
-                        match x == self.tiles[y].len() - 1 {
-                            true => vec![c],
-                            false => {
-                                let n1 = self.get_pipe_neighbors(x, y);
-                                let n2 = self.get_pipe_neighbors(x + 1, y);
-                                let n = match n1.contains(&(x + 1, y)) && n2.contains(&(x, y)) {
-                                    true => '-',
-                                    false => '*',
-                                };
-                                vec![c, n]
-                            }
-                        }
-                    })
-                    .collect_vec()
-            })
-            .collect();
+        let width = (self.columns() * 2).saturating_sub(1);
+        self.tiles = Grid::from_fn(width, self.rows(), |x, y| match x % 2 == 0 {
+            true => self.tiles[(x / 2, y)],
+            false => {
+                let x = x / 2;
+                let p1 = Point::new(x, y);
+                match self.connected(p1, Direction::East) {
+                    true => char::from(Pipe::Horizontal),
+                    false => Pipe::GAP,
+                }
+            }
+        });
     }
 
     pub fn pretty_print(&self) -> Result<String, Report> {
         let mut table = Table::new();
-        table.headers = (0..self.tiles[0].len()).map(|n| n.to_string()).collect_vec();
+        table.headers = (0..self.columns()).map(|n| n.to_string()).collect_vec();
         table.headers.insert(0, "y/x".to_string());
         table.rows = self
             .tiles
-            .iter()
+            .rows()
             .enumerate()
             .map(|(y, row)| {
                 let mut row = row.iter().map(|c| c.to_string()).collect_vec();
@@ -317,11 +389,18 @@ impl Map {
                 row
             })
             .collect_vec();
-        let mut md = table.to_markdown()?;
-        md = md.replace("| |", "| X");
-        md = md.replace('|', "");
-        md = md.replace('X', "|");
-        Ok(md)
+        // maps read best without borders, unless another format was chosen
+        let format = match table::output_format() {
+            Format::Markdown => Format::Compact,
+            format => format,
+        };
+        // page wide maps by the terminal width, repeating the row numbers
+        let layout = table::output_layout();
+        let layout = Layout {
+            width: layout.width.or(Layout::terminal().width),
+            ..layout
+        };
+        table.to_layout(format, &layout)
     }
 }
 
@@ -329,12 +408,7 @@ impl FromStr for Map {
     type Err = Report;
 
     fn from_str(input: &str) -> Result<Self, Report> {
-        let mut input = input.to_string();
-
-        if input.ends_with('\n') || input.ends_with('\r') {
-            input.pop();
-        }
-        let tiles = input.split('\n').map(|l| l.chars().collect_vec()).collect_vec();
+        let tiles = Grid::from_str(input)?;
         let map = Map { tiles };
         Ok(map)
     }
@@ -364,8 +438,96 @@ impl std::fmt::Display for Tile {
     }
 }
 
+/// Where a tile is relative to a loop, see [`Map::scanline`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    Inside,
+    Outside,
+    Loop,
+}
+
+impl From<Region> for char {
+    /// Label a region, to render with [`Map::pretty_print`].
+    fn from(region: Region) -> char {
+        match region {
+            Region::Inside => 'I',
+            Region::Outside => 'O',
+            Region::Loop => '*',
+        }
+    }
+}
+
+/// Connected regions of a map, see [`Map::components`].
+#[derive(Clone, Debug)]
+pub struct Components {
+    /// Region of each tile, or `None` if the tile didn't match.
+    pub labels: Grid<Option<usize>>,
+    /// Number of tiles in each region.
+    pub sizes: Vec<usize>,
+    /// Whether each region reaches the edge of the map.
+    pub touches_border: Vec<bool>,
+}
+
 #[derive(Clone, Copy)]
 pub enum Fill {
     Flood,
     Loop,
 }
+
+#[test]
+fn components() -> Result<(), Report> {
+    let map = Map::from_str("..#..\n..#..\n###..\n.#...\n.#...")?;
+    let components = map.components(|c| *c == '.');
+    assert_eq!(components.sizes, [4, 12, 2]);
+    assert_eq!(components.touches_border, [true, true, true]);
+    assert_eq!(components.labels[(4, 4)], Some(1));
+    assert_eq!(components.labels[(2, 0)], None);
+
+    let map = Map::from_str("#####\n#.#.#\n#####")?;
+    let components = map.components(|c| *c == '.');
+    assert_eq!(components.sizes, [1, 1]);
+    assert_eq!(components.touches_border, [false, false]);
+    assert_eq!(map.flood_fill(Point::new(1, 1), false), [Point::new(1, 1)]);
+    Ok(())
+}
+
+#[test]
+fn tilt() -> Result<(), Report> {
+    // 2023 day 14, roll the rounded rocks north
+    let mut map = Map::from_str("O....#....\nO.OO#....#\n.....##...\nOO.#O....O\n.O.....O#.")?;
+    map.tilt('O', '.', Direction::North);
+    assert_eq!(
+        map.tiles.to_string(),
+        "OOOO.#.O..\nOO..#....#\nO...O##..O\n...#......\n........#."
+    );
+
+    map.tilt('O', '.', Direction::SouthEast);
+    let expected = "...O.#....\n.O..#...O#\n..O..##..O\n...#.O....\n..OO.OO.#.";
+    assert_eq!(map.tiles.to_string(), expected);
+    Ok(())
+}
+
+#[test]
+fn scanline() -> Result<(), Report> {
+    // 2023 day 10, squeezing between pipes doesn't count as inside
+    let map = Map::from_str(
+        "..........\n.S------7.\n.|F----7|.\n.||OOOO||.\n.||OOOO||.\n.|L-7F-J|.\n.|II||II|.\n.L--JL--J.\n..........",
+    )?;
+    let regions = map.scanline(map.search(&'S')[0])?;
+    let labels = Map {
+        tiles: regions.map(|r| char::from(*r)),
+    };
+    assert_eq!(labels.search(&'I').len(), 4);
+    assert_eq!(labels.tiles.row(6).iter().collect::<String>(), "O*II**II*O");
+    assert!(labels.pretty_print()?.contains('I'));
+    Ok(())
+}
+
+#[test]
+fn trace_loop() -> Result<(), Report> {
+    // the cross sends the trace north every time, around a loop without the start
+    let map = Map::from_str(".....\n..F7.\nS-+J.\n|....")?;
+    let error = map.trace_loop(Point::new(0, 2)).unwrap_err();
+    assert_eq!(error.to_string(), "Pipe loop doesn't return to the start.");
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::game::{Direction, Map, Point};
+
+use std::borrow::Cow;
+
+/// Which cells count as neighbors, as `(dx, dy)` offsets from a cell.
+///
+/// Offsets that land off the map are skipped, unless the neighborhood wraps
+/// around, in which case the map's edges are joined like a torus.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Neighborhood {
+    pub offsets: Cow<'static, [(isize, isize)]>,
+    pub wrap: bool,
+}
+
+impl Neighborhood {
+    /// The four cardinal neighbors.
+    pub const VON_NEUMANN: Neighborhood =
+        Neighborhood::from_static(&[(0, -1), (1, 0), (0, 1), (-1, 0)]);
+
+    /// All eight neighbors, including diagonals, clockwise from north.
+    pub const MOORE: Neighborhood = Neighborhood::from_static(&[
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ]);
+
+    const fn from_static(offsets: &'static [(isize, isize)]) -> Self {
+        Neighborhood {
+            offsets: Cow::Borrowed(offsets),
+            wrap: false,
+        }
+    }
+
+    /// Any offsets, like the L-shaped moves of a knight.
+    pub fn custom(offsets: Vec<(isize, isize)>) -> Self {
+        Neighborhood {
+            offsets: Cow::Owned(offsets),
+            wrap: false,
+        }
+    }
+
+    /// The offsets of steps in some directions.
+    pub fn directions(directions: &[Direction]) -> Self {
+        Neighborhood::custom(directions.iter().map(|d| d.offset()).collect())
+    }
+
+    /// The same offsets, joining opposite edges of the map.
+    pub fn wrapping(self) -> Self {
+        Neighborhood { wrap: true, ..self }
+    }
+
+    /// Iterate over the neighbors of a cell, on a map of some size.
+    pub fn around(
+        &self,
+        p: Point,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = Point> + '_ {
+        let wrap = self.wrap;
+        self.offsets.iter().filter_map(move |(dx, dy)| {
+            if wrap && width > 0 && height > 0 {
+                let x = (p.x as isize + dx).rem_euclid(width as isize) as usize;
+                let y = (p.y as isize + dy).rem_euclid(height as isize) as usize;
+                return Some(Point::new(x, y));
+            }
+            p.checked_add(*dx, *dy).filter(|n| n.x < width && n.y < height)
+        })
+    }
+}
+
+impl Map {
+    /// Iterate over the neighbors of a tile, in a neighborhood.
+    pub fn neighbors<'a>(
+        &self,
+        p: Point,
+        neighborhood: &'a Neighborhood,
+    ) -> impl Iterator<Item = Point> + 'a {
+        neighborhood.around(p, self.columns(), self.rows())
+    }
+}
+
+#[test]
+fn neighborhoods() -> Result<(), color_eyre::eyre::Report> {
+    use itertools::Itertools;
+    use std::str::FromStr;
+
+    let map = Map::from_str("abc\ndef\nghi")?;
+    let tiles = |neighborhood: &Neighborhood, p: Point| {
+        map.neighbors(p, neighborhood).map(|n| map.tiles[n]).collect::<String>()
+    };
+    let corner = Point::new(0, 0);
+    assert_eq!(tiles(&Neighborhood::VON_NEUMANN, corner), "bd");
+    assert_eq!(tiles(&Neighborhood::MOORE, corner), "bed");
+    assert_eq!(tiles(&Neighborhood::MOORE, Point::new(1, 1)), "bcfihgda");
+    assert_eq!(tiles(&Neighborhood::VON_NEUMANN.wrapping(), corner), "gbdc");
+
+    let knight = Neighborhood::custom(vec![(1, 2), (2, 1), (-1, 2), (-2, 1)]);
+    assert_eq!(tiles(&knight, corner), "hf");
+    let diagonals = Direction::ALL.into_iter().filter(|d| d.is_diagonal()).collect_vec();
+    assert_eq!(
+        tiles(&Neighborhood::directions(&diagonals), Point::new(1, 1)),
+        "ciga"
+    );
+
+    assert!(map.get_neighbors(corner).eq(map.neighbors(corner, &Neighborhood::MOORE)));
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use crate::game::{Direction, Grid, Map, Point};
+
+use color_eyre::eyre::{Report, Result};
+use itertools::Itertools;
+use std::str::FromStr;
+
+// ----------------------------------------------------------------------------
+// String search
+// ----------------------------------------------------------------------------
+
+/// Where a string was found, its first character and the direction it reads in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StrMatch {
+    pub point: Point,
+    pub direction: Direction,
+}
+
+impl Map {
+    /// Check if a string reads from a point in a direction.
+    fn reads(&self, query: &[char], point: Point, direction: Direction) -> bool {
+        let mut p = Some(point);
+        query.iter().all(|c| match p {
+            Some(q) if self.tiles.get(q.x, q.y) == Some(c) => {
+                p = q.step(direction);
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Find a string along rows, columns and diagonals, in all eight directions.
+    ///
+    /// A palindrome (or single character) is found once per direction it reads in.
+    pub fn search_str(&self, query: &str) -> Vec<StrMatch> {
+        let query = query.chars().collect_vec();
+        let Some(first) = query.first() else {
+            return Vec::new();
+        };
+        self.search(first)
+            .into_iter()
+            .flat_map(|point| Direction::ALL.map(|direction| StrMatch { point, direction }))
+            .filter(|m| self.reads(&query, m.point, m.direction))
+            .collect_vec()
+    }
+
+    /// Count the matches of [`Map::search_str`], without collecting them.
+    pub fn count_str(&self, query: &str) -> usize {
+        let query = query.chars().collect_vec();
+        let Some(first) = query.first() else {
+            return 0;
+        };
+        self.tiles
+            .iter()
+            .filter(|(_, c)| *c == first)
+            .map(|(p, _)| Direction::ALL.iter().filter(|d| self.reads(&query, p, **d)).count())
+            .sum()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 2D pattern search
+// ----------------------------------------------------------------------------
+
+/// A rectangular pattern of tiles, where `None` cells match any tile.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    pub cells: Grid<Option<char>>,
+}
+
+impl Pattern {
+    /// Parse a pattern from lines of text, where the wildcard matches any tile.
+    pub fn new(text: &str, wildcard: char) -> Result<Self, Report> {
+        let tiles = Grid::from_str(text)?;
+        let cells = tiles.map(|c| (*c != wildcard).then_some(*c));
+        Ok(Pattern { cells })
+    }
+
+    /// The pattern turned to an orientation.
+    pub fn orient(&self, orientation: Orientation) -> Pattern {
+        let cells = match orientation.flipped {
+            true => self.cells.flip_horizontal(),
+            false => self.cells.clone(),
+        };
+        Pattern {
+            cells: cells.rotate(orientation.quarter_turns),
+        }
+    }
+
+    /// Every distinct orientation of the pattern, a symmetric pattern has fewer.
+    pub fn orientations(&self) -> Vec<(Orientation, Pattern)> {
+        let orientations = [false, true].into_iter().cartesian_product(0..4);
+        orientations
+            .map(|(flipped, quarter_turns)| {
+                let orientation = Orientation {
+                    quarter_turns,
+                    flipped,
+                };
+                (orientation, self.orient(orientation))
+            })
+            .unique_by(|(_, pattern)| pattern.clone())
+            .collect_vec()
+    }
+}
+
+/// How a pattern was turned to match, flipped left to right first and then
+/// rotated clockwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub quarter_turns: usize,
+    pub flipped: bool,
+}
+
+/// Where a pattern was found, its top left corner and orientation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    pub point: Point,
+    pub orientation: Orientation,
+}
+
+impl Map {
+    /// Check if a pattern matches with its top left corner at a point.
+    fn fits(&self, pattern: &Pattern, point: Point) -> bool {
+        let (w, h) = (pattern.cells.width(), pattern.cells.height());
+        if point.x + w > self.columns() || point.y + h > self.rows() {
+            return false;
+        }
+        pattern.cells.iter().all(|(p, cell)| match cell {
+            Some(c) => self.tiles[(point.x + p.x, point.y + p.y)] == *c,
+            None => true,
+        })
+    }
+
+    /// Find a pattern in every orientation, see [`Pattern::orientations`].
+    pub fn search_pattern(&self, pattern: &Pattern) -> Vec<PatternMatch> {
+        pattern
+            .orientations()
+            .into_iter()
+            .flat_map(|(orientation, pattern)| {
+                let points = self.tiles.iter().map(|(p, _)| p);
+                let points = points.filter(|p| self.fits(&pattern, *p)).collect_vec();
+                points.into_iter().map(move |point| PatternMatch { point, orientation })
+            })
+            .collect_vec()
+    }
+
+    /// Count the matches of [`Map::search_pattern`], without collecting them.
+    pub fn count_pattern(&self, pattern: &Pattern) -> usize {
+        let orientations = pattern.orientations();
+        let fits = |p: &Point| orientations.iter().filter(|(_, o)| self.fits(o, *p)).count();
+        self.tiles.iter().map(|(p, _)| fits(&p)).sum()
+    }
+}
+
+#[test]
+fn patterns() -> Result<(), Report> {
+    // 2024 day 4
+    let map = Map::from_str(
+        "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\n\
+         XXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX",
+    )?;
+    assert_eq!(map.count_str("XMAS"), 18);
+    let matches = map.search_str("XMAS");
+    assert_eq!(matches.len(), 18);
+    let expected = StrMatch {
+        point: Point::new(5, 0),
+        direction: Direction::East,
+    };
+    assert!(matches.contains(&expected));
+
+    let pattern = Pattern::new("M?S\n?A?\nM?S", '?')?;
+    assert_eq!(pattern.orientations().len(), 4);
+    assert_eq!(map.count_pattern(&pattern), 9);
+    assert_eq!(map.search_pattern(&pattern).len(), 9);
+    Ok(())
+}
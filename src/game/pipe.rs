@@ -0,0 +1,161 @@
+use crate::game::{Direction, Map, Point};
+
+use color_eyre::eyre::{eyre, Report, Result};
+use itertools::Itertools;
+
+/// A pipe tile, and the directions it connects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pipe {
+    /// `|` connects north and south.
+    Vertical,
+    /// `-` connects east and west.
+    Horizontal,
+    /// `L` connects north and east.
+    NorthEast,
+    /// `J` connects north and west.
+    NorthWest,
+    /// `7` connects south and west.
+    SouthWest,
+    /// `F` connects south and east.
+    SouthEast,
+    /// `S` hides an unknown pipe, so it could connect in any direction.
+    Start,
+    /// `+` connects in every direction.
+    Cross,
+}
+
+impl Pipe {
+    /// Pipes with exactly two connections, that can be under the start.
+    pub const BENDS: [Pipe; 6] = [
+        Pipe::Vertical,
+        Pipe::Horizontal,
+        Pipe::NorthEast,
+        Pipe::NorthWest,
+        Pipe::SouthWest,
+        Pipe::SouthEast,
+    ];
+
+    /// Tile placed between pipes that don't connect, when pushing them apart.
+    pub const GAP: char = '*';
+
+    /// Directions the pipe connects, in the order of [`Direction::CARDINAL`].
+    pub fn connections(&self) -> &'static [Direction] {
+        use Direction::*;
+
+        match self {
+            Pipe::Vertical => &[North, South],
+            Pipe::Horizontal => &[East, West],
+            Pipe::NorthEast => &[North, East],
+            Pipe::NorthWest => &[North, West],
+            Pipe::SouthWest => &[South, West],
+            Pipe::SouthEast => &[East, South],
+            Pipe::Start | Pipe::Cross => &Direction::CARDINAL,
+        }
+    }
+
+    pub fn connects(&self, direction: Direction) -> bool {
+        self.connections().contains(&direction)
+    }
+
+    /// The pipe that connects exactly these directions, in any order.
+    pub fn from_connections(directions: &[Direction]) -> Option<Pipe> {
+        let directions = directions.iter().sorted().collect_vec();
+        Pipe::BENDS
+            .into_iter()
+            .find(|p| p.connections().iter().sorted().collect_vec() == directions)
+    }
+}
+
+impl TryFrom<char> for Pipe {
+    type Error = Report;
+
+    fn try_from(c: char) -> Result<Self, Report> {
+        let pipe = match c {
+            '|' => Pipe::Vertical,
+            '-' => Pipe::Horizontal,
+            'L' => Pipe::NorthEast,
+            'J' => Pipe::NorthWest,
+            '7' => Pipe::SouthWest,
+            'F' => Pipe::SouthEast,
+            'S' => Pipe::Start,
+            '+' => Pipe::Cross,
+            _ => return Err(eyre!("'{c}' is not a pipe.")),
+        };
+        Ok(pipe)
+    }
+}
+
+impl From<Pipe> for char {
+    fn from(pipe: Pipe) -> char {
+        match pipe {
+            Pipe::Vertical => '|',
+            Pipe::Horizontal => '-',
+            Pipe::NorthEast => 'L',
+            Pipe::NorthWest => 'J',
+            Pipe::SouthWest => '7',
+            Pipe::SouthEast => 'F',
+            Pipe::Start => 'S',
+            Pipe::Cross => '+',
+        }
+    }
+}
+
+impl Map {
+    /// Get the pipe on a tile, if it is one.
+    pub fn pipe(&self, p: Point) -> Option<Pipe> {
+        Pipe::try_from(self.tiles[p]).ok()
+    }
+
+    /// Check if a tile and its neighbor in a direction both connect to each other.
+    pub fn connected(&self, p: Point, direction: Direction) -> bool {
+        let Some(n) = self.step(p, direction) else {
+            return false;
+        };
+        let connects = |p: Point, d: Direction| self.pipe(p).is_some_and(|pipe| pipe.connects(d));
+        connects(p, direction) && connects(n, direction.opposite())
+    }
+
+    /// Find every pipe connection that isn't connected back, or leads off the map.
+    ///
+    /// The start and crosses connect anywhere, so only their neighbors are checked.
+    pub fn dangling_pipes(&self) -> Vec<(Point, Direction)> {
+        let pipes = self.tiles.iter().filter_map(|(p, _)| Some((p, self.pipe(p)?)));
+        pipes
+            .filter(|(_, pipe)| !matches!(pipe, Pipe::Start | Pipe::Cross))
+            .flat_map(|(p, pipe)| pipe.connections().iter().map(move |d| (p, *d)))
+            .filter(|(p, d)| !self.connected(*p, *d))
+            .collect_vec()
+    }
+
+    /// Infer the pipe hidden under the start, from the neighbors that connect to it.
+    pub fn infer_start(&self, start: Point) -> Result<Pipe, Report> {
+        let connected =
+            Direction::CARDINAL.into_iter().filter(|d| self.connected(start, *d)).collect_vec();
+        Pipe::from_connections(&connected).ok_or_else(|| {
+            eyre!(
+                "Start at {start} connects to {} pipes, instead of 2.",
+                connected.len()
+            )
+        })
+    }
+}
+
+#[test]
+fn pipes() -> Result<(), Report> {
+    use std::str::FromStr;
+
+    let map = Map::from_str(".....\n.S-7.\n.|.|.\n.L-J-\n.....")?;
+    let start = Point::new(1, 1);
+    assert_eq!(map.infer_start(start)?, Pipe::SouthEast);
+    assert!(map.connected(start, Direction::East));
+    assert!(!map.connected(Point::new(3, 3), Direction::East));
+    // the last pipe leads off the map, and the bend next to it doesn't connect back
+    let dangling = [Direction::East, Direction::West].map(|d| (Point::new(4, 3), d));
+    assert_eq!(map.dangling_pipes(), dangling);
+
+    for pipe in Pipe::BENDS {
+        assert_eq!(Pipe::try_from(char::from(pipe))?, pipe);
+        assert_eq!(Pipe::from_connections(pipe.connections()), Some(pipe));
+    }
+    Ok(())
+}
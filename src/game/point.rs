@@ -0,0 +1,153 @@
+// ----------------------------------------------------------------------------
+// Point
+// ----------------------------------------------------------------------------
+
+/// A grid coordinate, `x` is the column and `y` is the row (down is positive).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Point { x, y }
+    }
+
+    /// Move by an offset, or `None` if that goes below zero.
+    pub fn checked_add(&self, dx: isize, dy: isize) -> Option<Point> {
+        Some(Point {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+
+    /// Take one step in a direction, or `None` if that goes below zero.
+    pub fn step(&self, direction: Direction) -> Option<Point> {
+        let (dx, dy) = direction.offset();
+        self.checked_add(dx, dy)
+    }
+
+    /// Distance moving in the four cardinal directions.
+    pub fn manhattan(&self, other: &Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Distance moving in all eight directions, like a king in chess.
+    pub fn chebyshev(&self, other: &Point) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Straight line distance.
+    pub fn euclidean(&self, other: &Point) -> f64 {
+        let (dx, dy) = (
+            self.x.abs_diff(other.x) as f64,
+            self.y.abs_diff(other.y) as f64,
+        );
+        dx.hypot(dy)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Direction
+// ----------------------------------------------------------------------------
+
+/// A compass direction on the grid, north is up (decreasing `y`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// North, east, south and west.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// All eight directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The `(dx, dy)` of one step in this direction.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    /// Rotate clockwise by a number of eighths of a turn (negative is counter-clockwise).
+    pub fn rotate(&self, eighths: isize) -> Direction {
+        let i = Direction::ALL.iter().position(|d| d == self).unwrap_or(0) as isize;
+        Direction::ALL[(i + eighths).rem_euclid(8) as usize]
+    }
+
+    /// Turn 90 degrees clockwise.
+    pub fn turn_right(&self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// Turn 90 degrees counter-clockwise.
+    pub fn turn_left(&self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !Direction::CARDINAL.contains(self)
+    }
+}
+
+#[test]
+fn points() {
+    let p = Point::new(1, 2);
+    assert_eq!(p.step(Direction::NorthWest), Some(Point::new(0, 1)));
+    assert_eq!(Point::new(0, 0).step(Direction::North), None);
+    assert_eq!(p.manhattan(&Point::new(4, 6)), 7);
+    assert_eq!(p.chebyshev(&Point::new(4, 6)), 4);
+    assert_eq!(p.euclidean(&Point::new(4, 6)), 5.0);
+
+    assert_eq!(Direction::North.turn_right(), Direction::East);
+    assert_eq!(Direction::North.turn_left(), Direction::West);
+    assert_eq!(Direction::SouthWest.opposite(), Direction::NorthEast);
+    assert!(Direction::SouthWest.is_diagonal());
+}
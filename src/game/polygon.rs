@@ -0,0 +1,56 @@
+use crate::game::Point;
+
+use itertools::Itertools;
+
+/// Twice the area of a polygon, by the shoelace formula.
+///
+/// The vertices are in order (either way round), and the last connects back
+/// to the first. Doubling keeps the area of lattice polygons a whole number.
+pub fn doubled_area(vertices: &[Point]) -> usize {
+    let sum: isize = vertices
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| a.x as isize * b.y as isize - b.x as isize * a.y as isize)
+        .sum();
+    sum.unsigned_abs()
+}
+
+/// Area of a polygon, by the shoelace formula.
+pub fn area(vertices: &[Point]) -> f64 {
+    doubled_area(vertices) as f64 / 2.0
+}
+
+/// Number of lattice points on the boundary of a polygon, including the vertices.
+pub fn boundary(vertices: &[Point]) -> usize {
+    let gcd = |mut a: usize, mut b: usize| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    let edges = vertices.iter().circular_tuple_windows();
+    edges.map(|(a, b)| gcd(a.x.abs_diff(b.x), a.y.abs_diff(b.y))).sum()
+}
+
+/// Number of lattice points strictly inside a polygon, by Pick's theorem.
+///
+/// Pick's theorem relates the area to the interior and boundary points,
+/// `A = I + B/2 - 1`, so `I = (2A - B + 2) / 2`.
+pub fn interior(vertices: &[Point]) -> usize {
+    (doubled_area(vertices) + 2).saturating_sub(boundary(vertices)) / 2
+}
+
+#[test]
+fn polygons() {
+    // a 4x3 rectangle, with corners only
+    let rectangle = [(0, 0), (4, 0), (4, 3), (0, 3)].map(Point::from);
+    assert_eq!(area(&rectangle), 12.0);
+    assert_eq!(boundary(&rectangle), 14);
+    assert_eq!(interior(&rectangle), 6);
+
+    // a triangle, listed the other way round
+    let triangle = [(0, 0), (0, 4), (4, 0)].map(Point::from);
+    assert_eq!(doubled_area(&triangle), 16);
+    assert_eq!(boundary(&triangle), 12);
+    assert_eq!(interior(&triangle), 3);
+}
@@ -0,0 +1,320 @@
+use crate::game::{Map, Pipe, Point};
+
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::io::IsTerminal;
+
+// ----------------------------------------------------------------------------
+// Styles
+// ----------------------------------------------------------------------------
+
+/// The eight standard terminal colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+/// How a tile is drawn, unset fields leave the terminal's default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    /// Character drawn instead of the tile.
+    pub glyph: Option<char>,
+}
+
+impl Style {
+    pub fn foreground(color: Color) -> Self {
+        Style {
+            foreground: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn background(color: Color) -> Self {
+        Style {
+            background: Some(color),
+            ..Default::default()
+        }
+    }
+
+    /// Layer this style on top of another, the fields set here win.
+    pub fn over(&self, below: &Style) -> Style {
+        Style {
+            foreground: self.foreground.or(below.foreground),
+            background: self.background.or(below.background),
+            bold: self.bold || below.bold,
+            glyph: self.glyph.or(below.glyph),
+        }
+    }
+
+    /// Draw a tile, wrapped in ANSI escape codes if `color` is set.
+    pub fn paint(&self, tile: char, color: bool) -> String {
+        let glyph = self.glyph.unwrap_or(tile);
+        let bold = self.bold.then_some(1);
+        let foreground = self.foreground.map(|c| 30 + c as u8);
+        let background = self.background.map(|c| 40 + c as u8);
+        let codes = [bold, foreground, background].into_iter().flatten().collect_vec();
+        match color && !codes.is_empty() {
+            true => format!("\x1b[{}m{glyph}\x1b[0m", codes.iter().join(";")),
+            false => glyph.to_string(),
+        }
+    }
+}
+
+/// The style of every tile of one kind, and its name in the legend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileStyle {
+    pub tile: char,
+    pub name: String,
+    pub style: Style,
+}
+
+/// A style layered over a set of points, like a path or a region.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overlay {
+    pub name: String,
+    pub points: HashSet<Point>,
+    pub style: Style,
+}
+
+/// The part of a map to draw, `width` columns and `height` rows from the top
+/// left corner `(x, y)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Viewport {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    /// A viewport centered on a point, moved inside the map where it fits.
+    pub fn around(center: Point, width: usize, height: usize, map: &Map) -> Self {
+        Viewport {
+            x: center.x.saturating_sub(width / 2).min(map.columns().saturating_sub(width)),
+            y: center.y.saturating_sub(height / 2).min(map.rows().saturating_sub(height)),
+            width,
+            height,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Renderer
+// ----------------------------------------------------------------------------
+
+/// Draws a map for the terminal, styling each kind of tile and any overlays.
+///
+/// Overlays are layered in the order they were added, over the tile styles.
+/// Colors default to on when printing to a terminal, unless `NO_COLOR` is set.
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    pub tiles: Vec<TileStyle>,
+    pub overlays: Vec<Overlay>,
+    /// Crop the map, otherwise the whole map is drawn.
+    pub viewport: Option<Viewport>,
+    /// Number the rows and columns.
+    pub ruler: bool,
+    /// List the styled tiles and overlays below the map.
+    pub legend: bool,
+    /// Use ANSI escape codes.
+    pub color: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer {
+            tiles: Vec::new(),
+            overlays: Vec::new(),
+            viewport: None,
+            ruler: false,
+            legend: true,
+            color: std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Style every tile of one kind.
+    pub fn tile(mut self, tile: char, name: &str, style: Style) -> Self {
+        self.tiles.retain(|t| t.tile != tile);
+        self.tiles.push(TileStyle {
+            tile,
+            name: name.to_string(),
+            style,
+        });
+        self
+    }
+
+    /// Style a set of points, over everything added before.
+    pub fn overlay(
+        mut self,
+        name: &str,
+        points: impl IntoIterator<Item = Point>,
+        style: Style,
+    ) -> Self {
+        self.overlays.push(Overlay {
+            name: name.to_string(),
+            points: points.into_iter().collect(),
+            style,
+        });
+        self
+    }
+
+    /// Draw pipes with box-drawing characters, and the start in bold.
+    pub fn pipes() -> Self {
+        let glyphs = [
+            (Pipe::Vertical, '│'),
+            (Pipe::Horizontal, '─'),
+            (Pipe::NorthEast, '└'),
+            (Pipe::NorthWest, '┘'),
+            (Pipe::SouthWest, '┐'),
+            (Pipe::SouthEast, '┌'),
+            (Pipe::Cross, '┼'),
+        ];
+        let renderer = glyphs.into_iter().fold(Renderer::new(), |r, (pipe, glyph)| {
+            let style = Style {
+                glyph: Some(glyph),
+                ..Default::default()
+            };
+            r.tile(char::from(pipe), &format!("{pipe:?}"), style)
+        });
+        let start = Style {
+            bold: true,
+            ..Style::foreground(Color::Yellow)
+        };
+        renderer.tile('S', "Start", start)
+    }
+
+    /// The style of a tile, with its overlays.
+    pub fn style(&self, p: Point, tile: char) -> Style {
+        let style = self.tiles.iter().find(|t| t.tile == tile).map(|t| t.style);
+        let overlays = self.overlays.iter().filter(|o| o.points.contains(&p));
+        overlays.fold(style.unwrap_or_default(), |style, o| o.style.over(&style))
+    }
+
+    pub fn render(&self, map: &Map) -> String {
+        let view = self.viewport.unwrap_or(Viewport {
+            x: 0,
+            y: 0,
+            width: map.columns(),
+            height: map.rows(),
+        });
+        let xs = view.x..(view.x + view.width).min(map.columns());
+        let ys = view.y..(view.y + view.height).min(map.rows());
+        let mut lines = Vec::new();
+
+        // row numbers are right aligned, in front of the map
+        let number_width = ys.end.saturating_sub(1).to_string().len();
+        let margin = match self.ruler {
+            true => " ".repeat(number_width + 1),
+            false => String::new(),
+        };
+
+        // column numbers are written downwards, one line per digit
+        if self.ruler {
+            let digits = xs.end.saturating_sub(1).to_string().len() as u32;
+            for d in (0..digits).rev() {
+                let line = xs.clone().map(|x| match x / 10usize.pow(d) {
+                    0 if d > 0 => ' ',
+                    n => char::from_digit((n % 10) as u32, 10).unwrap_or(' '),
+                });
+                lines.push(format!("{margin}{}", line.collect::<String>()));
+            }
+        }
+
+        for y in ys.clone() {
+            let row = xs.clone().map(|x| {
+                let p = Point::new(x, y);
+                let tile = map.tiles[p];
+                self.style(p, tile).paint(tile, self.color)
+            });
+            let number = match self.ruler {
+                true => format!("{y:>number_width$} "),
+                false => String::new(),
+            };
+            lines.push(format!("{number}{}", row.collect::<String>()));
+        }
+
+        if self.legend {
+            let inside = |p: &Point| xs.contains(&p.x) && ys.contains(&p.y);
+            let shown = ys.clone().flat_map(|y| xs.clone().map(move |x| (x, y)));
+            let shown = shown.map(|p| map.tiles[p]).collect::<HashSet<char>>();
+            let tiles = self.tiles.iter().filter(|t| shown.contains(&t.tile));
+            let tiles = tiles.map(|t| (t.style.paint(t.tile, self.color), &t.name));
+            let overlays = self.overlays.iter().filter(|o| o.points.iter().any(inside));
+            let overlays = overlays.map(|o| (o.style.paint('■', self.color), &o.name));
+            let legend = tiles.chain(overlays).map(|(key, name)| format!("{key} {name}"));
+            let legend = legend.collect_vec();
+            if !legend.is_empty() {
+                lines.push(String::new());
+                lines.extend(legend);
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[test]
+fn render() -> Result<(), color_eyre::eyre::Report> {
+    use std::str::FromStr;
+
+    let map = Map::from_str("..........F-7\n..........|.|\n..........S-J")?;
+    let path = [(10, 0), (11, 0), (12, 0)].map(Point::from);
+    let renderer = Renderer {
+        ruler: true,
+        color: false,
+        ..Renderer::pipes()
+    };
+    let renderer = renderer.overlay("Path", path, Style::background(Color::Blue));
+    let expected = [
+        "            111",
+        "  0123456789012",
+        "0 ..........┌─┐",
+        "1 ..........│.│",
+        "2 ..........S─┘",
+        "",
+        "│ Vertical",
+        "─ Horizontal",
+        "┘ NorthWest",
+        "┐ SouthWest",
+        "┌ SouthEast",
+        "S Start",
+        "■ Path",
+    ];
+    assert_eq!(renderer.render(&map), expected.join("\n"));
+
+    // crop to the bottom right corner, the path is out of view
+    let renderer = Renderer {
+        viewport: Some(Viewport::around(Point::new(12, 2), 2, 2, &map)),
+        ruler: false,
+        ..renderer
+    };
+    assert_eq!(
+        renderer.render(&map),
+        ".│\n─┘\n\n│ Vertical\n─ Horizontal\n┘ NorthWest"
+    );
+
+    let renderer = Renderer {
+        viewport: Some(Viewport::around(Point::new(10, 2), 2, 2, &map)),
+        color: true,
+        legend: false,
+        ..renderer
+    };
+    assert_eq!(renderer.render(&map), ".│\n.\x1b[1;33mS\x1b[0m");
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use crate::game::{Direction, Grid, Map, Point};
+
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// ----------------------------------------------------------------------------
+// Search results
+// ----------------------------------------------------------------------------
+
+/// The states reached by a search, with their cost and where they came from.
+#[derive(Clone, Debug)]
+pub struct Search<S> {
+    /// Lowest cost found to reach each state.
+    pub costs: HashMap<S, usize>,
+    /// The state each state was reached from, the start has none.
+    pub parents: HashMap<S, S>,
+    /// The first goal state reached, if the search had a goal.
+    pub goal: Option<S>,
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+    fn new(start: S) -> Self {
+        Search {
+            costs: HashMap::from([(start, 0)]),
+            parents: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    /// Cost to reach a state, or `None` if it wasn't reached.
+    pub fn cost(&self, state: &S) -> Option<usize> {
+        self.costs.get(state).copied()
+    }
+
+    /// Reconstruct the path from the start to a state, including both ends.
+    pub fn path(&self, state: &S) -> Option<Vec<S>> {
+        self.costs.get(state)?;
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.parents.get(path.last()?) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Path to the goal and its cost, if a goal was reached.
+    pub fn goal_path(&self) -> Option<(Vec<S>, usize)> {
+        let goal = self.goal.as_ref()?;
+        Some((self.path(goal)?, self.cost(goal)?))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Algorithms
+// ----------------------------------------------------------------------------
+
+/// Breadth first search, where every move costs 1.
+///
+/// Stops at the first state that is a goal, or explores everything reachable.
+pub fn bfs<S: Clone + Eq + Hash>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> Vec<S>,
+    is_goal: impl Fn(&S) -> bool,
+) -> Search<S> {
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        let cost = search.costs[&state] + 1;
+        for next in neighbors(&state) {
+            if !search.costs.contains_key(&next) {
+                search.costs.insert(next.clone(), cost);
+                search.parents.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's algorithm, where `neighbors` returns each next state with the
+/// cost of moving there.
+pub fn dijkstra<S: Clone + Eq + Hash + Ord>(
+    start: S,
+    neighbors: impl FnMut(&S) -> Vec<(S, usize)>,
+    is_goal: impl Fn(&S) -> bool,
+) -> Search<S> {
+    astar(start, neighbors, |_| 0, is_goal)
+}
+
+/// A* search, where the heuristic estimates the remaining cost to a goal.
+///
+/// The heuristic must never overestimate, or the path found may not be the
+/// cheapest. A heuristic of zero is Dijkstra's algorithm.
+pub fn astar<S: Clone + Eq + Hash + Ord>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> Vec<(S, usize)>,
+    heuristic: impl Fn(&S) -> usize,
+    is_goal: impl Fn(&S) -> bool,
+) -> Search<S> {
+    let mut search = Search::new(start.clone());
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, start))]);
+
+    while let Some(Reverse((_, cost, state))) = queue.pop() {
+        // skip stale entries, a cheaper way here was already found
+        if cost > search.costs[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            if search.cost(&next).map_or(true, |c| next_cost < c) {
+                search.costs.insert(next.clone(), next_cost);
+                search.parents.insert(next.clone(), state.clone());
+                queue.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+            }
+        }
+    }
+
+    search
+}
+
+// ----------------------------------------------------------------------------
+// Map searches
+// ----------------------------------------------------------------------------
+
+/// A position on the map with the direction it was entered from, and how many
+/// steps were taken in a row in that direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Heading {
+    pub point: Point,
+    pub direction: Option<Direction>,
+    pub steps: usize,
+}
+
+impl Map {
+    /// Number of steps to every tile reachable from the start, moving in the
+    /// four cardinal directions onto passable tiles.
+    pub fn distances(&self, start: Point, passable: impl Fn(&char) -> bool) -> Grid<Option<usize>> {
+        let neighbors = |p: &Point| {
+            let n = self.get_simple_neighbors(*p);
+            n.filter(|n| passable(&self.tiles[*n])).collect_vec()
+        };
+        let search = bfs(start, neighbors, |_| false);
+
+        let mut distances = Grid::new(self.columns(), self.rows(), None);
+        search.costs.into_iter().for_each(|(p, cost)| distances[p] = Some(cost));
+        distances
+    }
+
+    /// Cheapest path between two tiles, moving in the four cardinal directions.
+    ///
+    /// The cost of entering a tile is given by `cost`, or `None` if it can't
+    /// be entered. Uses A* with the Manhattan distance, scaled by the cheapest
+    /// possible step.
+    pub fn shortest_path(
+        &self,
+        start: Point,
+        goal: Point,
+        min_cost: usize,
+        cost: impl Fn(&char) -> Option<usize>,
+    ) -> Option<(Vec<Point>, usize)> {
+        let neighbors = |p: &Point| {
+            let n = self.get_simple_neighbors(*p);
+            n.filter_map(|n| Some((n, cost(&self.tiles[n])?))).collect_vec()
+        };
+        let heuristic = |p: &Point| p.manhattan(&goal) * min_cost;
+        astar(start, neighbors, heuristic, |p| *p == goal).goal_path()
+    }
+
+    /// Cheapest path with constrained movement, searching over headings.
+    ///
+    /// `can_move` decides if a heading can move in a direction next, and
+    /// `is_goal` if a heading is a valid place to stop. The cost of entering a
+    /// tile is given by `cost`, or `None` if it can't be entered.
+    pub fn shortest_path_with(
+        &self,
+        start: Point,
+        cost: impl Fn(&char) -> Option<usize>,
+        can_move: impl Fn(&Heading, Direction) -> bool,
+        is_goal: impl Fn(&Heading) -> bool,
+    ) -> Option<(Vec<Heading>, usize)> {
+        let start = Heading {
+            point: start,
+            direction: None,
+            steps: 0,
+        };
+        let neighbors = |h: &Heading| {
+            let moves = Direction::CARDINAL.into_iter().filter(|d| can_move(h, *d));
+            moves
+                .filter_map(|d| {
+                    let point = self.step(h.point, d)?;
+                    let steps = match h.direction == Some(d) {
+                        true => h.steps + 1,
+                        false => 1,
+                    };
+                    let heading = Heading {
+                        point,
+                        direction: Some(d),
+                        steps,
+                    };
+                    Some((heading, cost(&self.tiles[point])?))
+                })
+                .collect_vec()
+        };
+        dijkstra(start, neighbors, is_goal).goal_path()
+    }
+}
+
+#[test]
+fn shortest_paths() -> Result<(), color_eyre::eyre::Report> {
+    use std::str::FromStr;
+
+    let map = Map::from_str("S..#\n.#.#\n...E")?;
+    let distances = map.distances(Point::new(0, 0), |c| *c != '#');
+    assert_eq!(distances[(3, 2)], Some(5));
+    assert_eq!(distances[(3, 0)], None);
+
+    let cost = |c: &char| (*c != '#').then_some(1);
+    let (path, cost) = map.shortest_path(Point::new(0, 0), Point::new(3, 2), 1, cost).unwrap();
+    assert_eq!((path.len(), cost), (6, 5));
+
+    // 2023 day 17, the crucible moves at most 3 tiles in a row, and can't reverse
+    let map = Map::from_str(
+        "2413432311323\n3215453535623\n3255245654254\n3446585845452\n4546657867536\n\
+         1438598798454\n4457876987766\n3637877979653\n4654967986887\n4564679986453\n\
+         1224686865563\n2546548887735\n4322674655533",
+    )?;
+    let goal = Point::new(map.columns() - 1, map.rows() - 1);
+    let cost = |c: &char| c.to_digit(10).map(|d| d as usize);
+    let crucible = |min: usize, max: usize| {
+        map.shortest_path_with(
+            Point::new(0, 0),
+            cost,
+            |h, d| match h.direction {
+                None => true,
+                Some(hd) if hd == d => h.steps < max,
+                Some(hd) => hd != d.opposite() && h.steps >= min,
+            },
+            |h| h.point == goal && h.steps >= min,
+        )
+    };
+    assert_eq!(crucible(1, 3).map(|(_, c)| c), Some(102));
+    assert_eq!(crucible(4, 10).map(|(_, c)| c), Some(94));
+    Ok(())
+}
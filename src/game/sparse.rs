@@ -0,0 +1,314 @@
+use crate::game::{Direction, Grid, Map, Point};
+
+use color_eyre::eyre::{eyre, Report, Result};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+// ----------------------------------------------------------------------------
+// Coordinates
+// ----------------------------------------------------------------------------
+
+/// A signed grid coordinate, for maps that grow in any direction, or span
+/// distances too large to store densely. Down is positive `y`, like [`Point`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Coord {
+    pub fn new(x: i64, y: i64) -> Self {
+        Coord { x, y }
+    }
+
+    /// Move a distance in a direction, which can't go off the map.
+    pub fn step(&self, direction: Direction, distance: i64) -> Coord {
+        let (dx, dy) = direction.offset();
+        Coord {
+            x: self.x + dx as i64 * distance,
+            y: self.y + dy as i64 * distance,
+        }
+    }
+
+    /// Distance moving in the four cardinal directions.
+    pub fn manhattan(&self, other: &Coord) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl From<Point> for Coord {
+    fn from(p: Point) -> Self {
+        Coord {
+            x: p.x as i64,
+            y: p.y as i64,
+        }
+    }
+}
+
+impl std::fmt::Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// The smallest rectangle containing a set of coordinates, `min` and `max`
+/// are both inside.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Coord,
+    pub max: Coord,
+}
+
+impl Bounds {
+    /// Bounds of some coordinates, or `None` if there are none.
+    pub fn new(coords: impl IntoIterator<Item = Coord>) -> Option<Self> {
+        let mut coords = coords.into_iter();
+        let first = coords.next()?;
+        let bounds = coords.fold((first, first), |(min, max), c| {
+            let min = Coord::new(min.x.min(c.x), min.y.min(c.y));
+            let max = Coord::new(max.x.max(c.x), max.y.max(c.y));
+            (min, max)
+        });
+        Some(Bounds {
+            min: bounds.0,
+            max: bounds.1,
+        })
+    }
+
+    pub fn width(&self) -> u64 {
+        self.max.x.abs_diff(self.min.x) + 1
+    }
+
+    pub fn height(&self) -> u64 {
+        self.max.y.abs_diff(self.min.y) + 1
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, c: Coord) -> bool {
+        (self.min.x..=self.max.x).contains(&c.x) && (self.min.y..=self.max.y).contains(&c.y)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Sparse map
+// ----------------------------------------------------------------------------
+
+/// A map that only stores the cells that are set, everything else is
+/// background. Coordinates can be negative or very far apart.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseMap<T = char> {
+    pub cells: HashMap<Coord, T>,
+}
+
+impl<T> SparseMap<T> {
+    pub fn new() -> Self {
+        SparseMap {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, c: Coord) -> Option<&T> {
+        self.cells.get(&c)
+    }
+
+    /// Set a cell, returning what was there before.
+    pub fn insert(&mut self, c: Coord, value: T) -> Option<T> {
+        self.cells.insert(c, value)
+    }
+
+    /// Reset a cell to the background, returning what was there before.
+    pub fn remove(&mut self, c: Coord) -> Option<T> {
+        self.cells.remove(&c)
+    }
+
+    /// Iterate over the set cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.cells.iter().map(|(c, v)| (*c, v))
+    }
+
+    /// Get the coordinates of every set cell that matches a predicate, sorted
+    /// row by row.
+    pub fn positions(&self, predicate: impl Fn(&T) -> bool) -> Vec<Coord> {
+        let positions = self.iter().filter(|(_, v)| predicate(v)).map(|(c, _)| c);
+        positions.sorted_by_key(|c| (c.y, c.x)).collect_vec()
+    }
+
+    /// Bounds of the set cells, or `None` if the map is empty.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::new(self.cells.keys().copied())
+    }
+
+    /// Grow every empty row and column inside the bounds into `factor` rows
+    /// or columns, moving the set cells apart.
+    pub fn expand(self, factor: u64) -> Self {
+        let xs = self.cells.keys().map(|c| c.x).sorted().dedup().collect_vec();
+        let ys = self.cells.keys().map(|c| c.y).sorted().dedup().collect_vec();
+        let (Some(x_min), Some(y_min)) = (xs.first().copied(), ys.first().copied()) else {
+            return self;
+        };
+
+        // the empty lines before a value, are the ones not taken by smaller values
+        let grow = factor as i64 - 1;
+        let shift = |values: &[i64], min: i64, v: i64| {
+            let taken = values.partition_point(|w| *w < v) as i64;
+            v + grow * (v - min - taken)
+        };
+        let cells = self.cells.into_iter().map(|(c, v)| {
+            let c = Coord::new(shift(&xs, x_min, c.x), shift(&ys, y_min, c.y));
+            (c, v)
+        });
+        SparseMap {
+            cells: cells.collect(),
+        }
+    }
+}
+
+impl<T: Clone> SparseMap<T> {
+    /// Copy into a dense grid covering the bounds, and the coordinate of its
+    /// top left corner.
+    ///
+    /// Fails if the grid would be too large, see [`Compression`] for maps
+    /// spread over large distances.
+    pub fn to_grid(&self, background: T, max_cells: u64) -> Result<(Grid<T>, Coord), Report> {
+        let Some(bounds) = self.bounds() else {
+            return Ok((
+                Grid::from_fn(0, 0, |_, _| background.clone()),
+                Coord::default(),
+            ));
+        };
+        if bounds.area() > max_cells {
+            return Err(eyre!(
+                "A {}x{} grid is larger than {max_cells} cells.",
+                bounds.width(),
+                bounds.height()
+            ));
+        }
+
+        let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+        let grid = Grid::from_fn(width, height, |x, y| {
+            let c = Coord::new(bounds.min.x + x as i64, bounds.min.y + y as i64);
+            self.get(c).cloned().unwrap_or_else(|| background.clone())
+        });
+        Ok((grid, bounds.min))
+    }
+}
+
+impl SparseMap<char> {
+    /// Keep every tile of a map that isn't the background.
+    pub fn from_map(map: &Map, background: char) -> Self {
+        let cells = map.tiles.iter().filter(|(_, c)| **c != background);
+        SparseMap {
+            cells: cells.map(|(p, c)| (Coord::from(p), *c)).collect(),
+        }
+    }
+
+    /// Copy into a dense map, shifted so the bounds start at `(0, 0)`.
+    pub fn to_map(&self, background: char, max_cells: u64) -> Result<Map, Report> {
+        let (tiles, _) = self.to_grid(background, max_cells)?;
+        Ok(Map { tiles })
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Coordinate compression
+// ----------------------------------------------------------------------------
+
+/// Squeeze far apart coordinates onto a small grid, keeping their order.
+///
+/// Each distinct `x` and `y` gets its own column or row, and the space between
+/// two of them is merged into the cell of the smaller one. A compressed cell
+/// covers [`Compression::area`] of the original cells.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Compression {
+    pub xs: Vec<i64>,
+    pub ys: Vec<i64>,
+}
+
+impl Compression {
+    pub fn new(coords: impl IntoIterator<Item = Coord>) -> Self {
+        let (xs, ys): (Vec<i64>, Vec<i64>) = coords.into_iter().map(|c| (c.x, c.y)).unzip();
+        Compression {
+            xs: xs.into_iter().sorted().dedup().collect_vec(),
+            ys: ys.into_iter().sorted().dedup().collect_vec(),
+        }
+    }
+
+    /// Width and height of the compressed grid.
+    pub fn size(&self) -> (usize, usize) {
+        (self.xs.len(), self.ys.len())
+    }
+
+    /// The compressed cell of a coordinate, or `None` if its `x` or `y` wasn't
+    /// one of the compressed values.
+    pub fn compress(&self, c: Coord) -> Option<Point> {
+        let x = self.xs.binary_search(&c.x).ok()?;
+        let y = self.ys.binary_search(&c.y).ok()?;
+        Some(Point::new(x, y))
+    }
+
+    /// The original coordinate of a compressed cell's top left corner.
+    pub fn decompress(&self, p: Point) -> Coord {
+        Coord::new(self.xs[p.x], self.ys[p.y])
+    }
+
+    /// Number of original cells covered by a compressed cell.
+    pub fn area(&self, p: Point) -> u64 {
+        let span = |values: &[i64], i: usize| match values.get(i + 1) {
+            Some(next) => next.abs_diff(values[i]),
+            None => 1,
+        };
+        span(&self.xs, p.x) * span(&self.ys, p.y)
+    }
+
+    /// A compressed grid with every cell set to the same value.
+    pub fn grid<T: Clone>(&self, value: T) -> Grid<T> {
+        let (width, height) = self.size();
+        Grid::new(width, height, value)
+    }
+}
+
+#[test]
+fn sparse() -> Result<(), Report> {
+    use std::str::FromStr;
+
+    // 2023 day 11, empty space grows 10 and 100 times
+    let map = Map::from_str(
+        "...#......\n.......#..\n#.........\n..........\n......#...\n\
+         .#........\n.........#\n..........\n.......#..\n#...#.....",
+    )?;
+    let distances = |factor| {
+        let galaxies = SparseMap::from_map(&map, '.').expand(factor).positions(|_| true);
+        let pairs = galaxies.iter().tuple_combinations();
+        pairs.map(|(g1, g2)| g1.manhattan(g2)).sum::<u64>()
+    };
+    assert_eq!(distances(10), 1030);
+    assert_eq!(distances(100), 8410);
+
+    let mut sparse = SparseMap::new();
+    sparse.insert(Coord::new(-2, 1), '#');
+    sparse.insert(Coord::new(0, -1), '#');
+    let bounds = sparse.bounds().unwrap();
+    assert_eq!((bounds.width(), bounds.height()), (3, 3));
+    assert!(bounds.contains(Coord::new(-1, 0)));
+    assert_eq!(sparse.to_map('.', 9)?.tiles.to_string(), "..#\n...\n#..");
+    assert!(sparse.to_grid('.', 8).is_err());
+
+    let compression = Compression::new([Coord::new(-3, 0), Coord::new(1_000_000, 5)]);
+    assert_eq!(compression.size(), (2, 2));
+    let p = compression.compress(Coord::new(-3, 5)).unwrap();
+    assert_eq!(p, Point::new(0, 1));
+    assert_eq!(compression.decompress(p), Coord::new(-3, 5));
+    assert_eq!(compression.area(Point::new(0, 0)), 1_000_003 * 5);
+    Ok(())
+}
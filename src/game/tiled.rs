@@ -0,0 +1,105 @@
+use crate::game::{bfs, Coord, Direction, Map, Point};
+
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// A map repeated forever in every direction, addressed by signed coordinates.
+///
+/// The copy at `(0, 0)` is the base map, and the copy at `(1, -1)` is one map
+/// to the right and one map up.
+#[derive(Clone)]
+pub struct TiledMap {
+    pub map: Map,
+}
+
+impl TiledMap {
+    pub fn new(map: Map) -> Self {
+        TiledMap { map }
+    }
+
+    /// The point on the base map a coordinate repeats, and which copy of the
+    /// map it lies in. Panics if the map is empty.
+    pub fn wrap(&self, c: Coord) -> (Point, Coord) {
+        let (width, height) = (self.map.columns() as i64, self.map.rows() as i64);
+        let point = Point::new(
+            c.x.rem_euclid(width) as usize,
+            c.y.rem_euclid(height) as usize,
+        );
+        let copy = Coord::new(c.x.div_euclid(width), c.y.div_euclid(height));
+        (point, copy)
+    }
+
+    /// The coordinate of a base map point, in a copy of the map.
+    pub fn unwrap(&self, point: Point, copy: Coord) -> Coord {
+        let (width, height) = (self.map.columns() as i64, self.map.rows() as i64);
+        Coord::new(
+            copy.x * width + point.x as i64,
+            copy.y * height + point.y as i64,
+        )
+    }
+
+    pub fn get(&self, c: Coord) -> char {
+        let (point, _) = self.wrap(c);
+        self.map.tiles[point]
+    }
+
+    /// Neighbors in the four cardinal directions, which always exist.
+    pub fn get_simple_neighbors(&self, c: Coord) -> impl Iterator<Item = Coord> {
+        Direction::CARDINAL.into_iter().map(move |d| c.step(d, 1))
+    }
+
+    /// Number of steps to every coordinate reachable from the start within a
+    /// number of steps, moving in the four cardinal directions onto passable
+    /// tiles.
+    pub fn distances(
+        &self,
+        start: Coord,
+        max_steps: usize,
+        passable: impl Fn(&char) -> bool,
+    ) -> HashMap<Coord, usize> {
+        // nothing further than max_steps can be reached in time, which keeps
+        // the search finite
+        let neighbors = |c: &Coord| {
+            let n = self.get_simple_neighbors(*c);
+            let n = n.filter(|n| n.manhattan(&start) <= max_steps as u64);
+            n.filter(|n| passable(&self.get(*n))).collect_vec()
+        };
+        let search = bfs(start, neighbors, |_| false);
+        let costs = search.costs.into_iter();
+        costs.filter(|(_, cost)| *cost <= max_steps).collect()
+    }
+
+    /// Number of coordinates that can be stood on after exactly a number of
+    /// steps, since any tile reached earlier can be returned to by stepping
+    /// back and forth.
+    pub fn reachable(&self, start: Coord, steps: usize, passable: impl Fn(&char) -> bool) -> usize {
+        let distances = self.distances(start, steps, passable);
+        distances.values().filter(|cost| *cost % 2 == steps % 2).count()
+    }
+}
+
+#[test]
+fn tiled() -> Result<(), color_eyre::eyre::Report> {
+    use std::str::FromStr;
+
+    // 2023 day 21, the garden repeats forever
+    let map = Map::from_str(
+        "...........\n.....###.#.\n.###.##..#.\n..#.#...#..\n....#.#....\n\
+         .##..S####.\n.##..#...#.\n.......##..\n.##.#.####.\n.##..##.##.\n...........",
+    )?;
+    let start = Coord::from(map.search(&'S')[0]);
+    let garden = TiledMap::new(map);
+
+    let c = Coord::new(-1, 23);
+    let (point, copy) = garden.wrap(c);
+    assert_eq!((point, copy), (Point::new(10, 1), Coord::new(-1, 2)));
+    assert_eq!(garden.unwrap(point, copy), c);
+    assert_eq!(garden.get(c), '.');
+
+    let plots = |steps| garden.reachable(start, steps, |c| *c != '#');
+    assert_eq!(plots(6), 16);
+    assert_eq!(plots(10), 50);
+    assert_eq!(plots(50), 1594);
+    assert_eq!(plots(100), 6536);
+    Ok(())
+}
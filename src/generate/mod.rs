@@ -0,0 +1,479 @@
+use color_eyre::eyre::{eyre, Report, Result};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeSet;
+
+// ----------------------------------------------------------------------------
+// Input dispatcher
+// ----------------------------------------------------------------------------
+
+/// Generate a random, structurally valid puzzle input for a day.
+///
+/// The `size` controls the scale of the input (number of lines, or the side
+/// length for the grid puzzles), and the same `seed` always produces the
+/// same input.
+pub fn generate(day: &str, size: usize, seed: u64) -> Result<String, Report> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.max(1);
+
+    let input = match day {
+        "1" => day_1(size, &mut rng),
+        "2" => day_2(size, &mut rng),
+        "3" => day_3(size, &mut rng),
+        "4" => day_4(size, &mut rng),
+        "5" => day_5(size, &mut rng),
+        "6" => day_6(size, &mut rng),
+        "7" => day_7(size, &mut rng),
+        "8" => day_8(size, &mut rng)?,
+        "9" => day_9(size, &mut rng),
+        "10" => day_10(size, &mut rng),
+        "11" => day_11(size, &mut rng),
+        "12" => day_12(size, &mut rng),
+        _ => return Err(eyre!("Day {day} does not have an input generator yet.")),
+    };
+
+    Ok(input)
+}
+
+// ----------------------------------------------------------------------------
+// Generators
+// ----------------------------------------------------------------------------
+
+/// Day 1 - Calibration lines of letters, digits and spelled out digits.
+pub fn day_1(size: usize, rng: &mut StdRng) -> String {
+    let names = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    (0..size)
+        .map(|_| {
+            let mut line = (0..rng.gen_range(1..=8))
+                .map(|_| match rng.gen_range(0..4) {
+                    0 => rng.gen_range(1..=9).to_string(),
+                    1 => names.choose(rng).unwrap().to_string(),
+                    _ => rng.gen_range('a'..='z').to_string(),
+                })
+                .collect_vec();
+
+            // every line needs at least one digit
+            let i = rng.gen_range(0..=line.len());
+            line.insert(i, rng.gen_range(1..=9).to_string());
+            line.join("")
+        })
+        .join("\n")
+}
+
+/// Day 2 - Cube games with a handful of draws each.
+pub fn day_2(size: usize, rng: &mut StdRng) -> String {
+    let colors = ["red", "green", "blue"];
+
+    (1..=size)
+        .map(|id| {
+            let draws = (0..rng.gen_range(1..=6))
+                .map(|_| {
+                    let amount = rng.gen_range(1..=colors.len());
+                    colors
+                        .choose_multiple(rng, amount)
+                        .map(|color| format!("{} {color}", rng.gen_range(1..=20)))
+                        .join(", ")
+                })
+                .join("; ");
+            format!("Game {id}: {draws}")
+        })
+        .join("\n")
+}
+
+/// Day 3 - Square engine schematic of part numbers and symbols.
+pub fn day_3(size: usize, rng: &mut StdRng) -> String {
+    let symbols = ['*', '#', '+', '$', '/', '@', '%', '=', '&', '-'];
+
+    (0..size)
+        .map(|_| {
+            let mut row = String::new();
+            while row.len() < size {
+                let remaining = size - row.len();
+                match rng.gen_range(0..6) {
+                    // part numbers are always followed by a gap, so they don't merge
+                    0 if remaining > 1 => {
+                        let digits = rng.gen_range(1..=3.min(remaining - 1));
+                        let number = rng.gen_range(
+                            10_usize.pow(digits as u32 - 1)..10_usize.pow(digits as u32),
+                        );
+                        row.push_str(&format!("{number}."));
+                    }
+                    1 => row.push(*symbols.choose(rng).unwrap()),
+                    _ => row.push('.'),
+                }
+            }
+            row
+        })
+        .join("\n")
+}
+
+/// Day 4 - Scratchcards, where winning copies never run past the end of the deck.
+pub fn day_4(size: usize, rng: &mut StdRng) -> String {
+    (1..=size)
+        .map(|id| {
+            let numbers = (1..100).collect_vec();
+            let mut numbers = numbers.choose_multiple(rng, 13).cloned().collect_vec();
+            let winning = numbers.split_off(8);
+
+            // mostly losing cards, to keep the number of copies in part 2 small
+            let matches = match rng.gen_bool(0.5) {
+                true => 0,
+                false => rng.gen_range(1..=3),
+            };
+            let matches = matches.min(size - id);
+            numbers[..matches].copy_from_slice(&winning[..matches]);
+            numbers.shuffle(rng);
+
+            let winning = winning.iter().map(|n| format!("{n:>2}")).join(" ");
+            let numbers = numbers.iter().map(|n| format!("{n:>2}")).join(" ");
+            format!("Card {id:>3}: {winning} | {numbers}")
+        })
+        .join("\n")
+}
+
+/// Day 5 - Almanac with seed ranges and non-overlapping category maps.
+pub fn day_5(size: usize, rng: &mut StdRng) -> String {
+    let categories = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let universe = 100 * size;
+
+    // seeds come in (start, length) pairs for part 2
+    let seeds = (0..size)
+        .flat_map(|_| {
+            let start = rng.gen_range(1..universe);
+            let length = rng.gen_range(1..=size * 10);
+            [start, length]
+        })
+        .join(" ");
+    let mut sections = vec![format!("seeds: {seeds}")];
+
+    categories.iter().tuple_windows().for_each(|(source, destination)| {
+        // cut the universe into ranges, and map every other range
+        let mut cuts = (0..size * 2).map(|_| rng.gen_range(1..universe)).collect::<BTreeSet<_>>();
+        cuts.insert(universe);
+        let ranges = cuts
+            .into_iter()
+            .tuple_windows()
+            .step_by(2)
+            .map(|(start, end)| {
+                let destination = rng.gen_range(1..universe);
+                format!("{destination} {start} {}", end - start)
+            })
+            .collect_vec();
+        let ranges = ranges.choose_multiple(rng, ranges.len()).join("\n");
+        sections.push(format!("{source}-to-{destination} map:\n{ranges}"));
+    });
+
+    sections.join("\n\n")
+}
+
+/// Day 6 - Boat races, where every record can be beaten.
+///
+/// Part 2 joins the races together into one long race, so there are at most
+/// four races to keep the joined race within a reasonable search.
+pub fn day_6(size: usize, rng: &mut StdRng) -> String {
+    let beatable = |time: u128, distance: u128| (time / 2) * (time - time / 2) > distance;
+
+    let (times, distances) = loop {
+        let (times, distances): (Vec<usize>, Vec<usize>) = (0..size.min(4))
+            .map(|_| {
+                let time = rng.gen_range(5..100);
+                let best = (time / 2) * (time - time / 2);
+                (time, rng.gen_range(0..best))
+            })
+            .unzip();
+
+        // the joined race of part 2 must be beatable too
+        let time = times.iter().join("").parse().unwrap();
+        let distance = distances.iter().join("").parse().unwrap();
+        if beatable(time, distance) {
+            break (times, distances);
+        }
+    };
+
+    let times = times.iter().map(|n| format!("{n:>4}")).join(" ");
+    let distances = distances.iter().map(|n| format!("{n:>4}")).join(" ");
+    format!("Time:     {times}\nDistance: {distances}")
+}
+
+/// Day 7 - Unique camel card hands with bids.
+pub fn day_7(size: usize, rng: &mut StdRng) -> String {
+    let labels = "23456789TJQKA".chars().collect_vec();
+    let mut hands = BTreeSet::new();
+
+    // there are only 13^5 distinct hands
+    let size = size.min(labels.len().pow(5));
+
+    (0..size)
+        .map(|_| {
+            let mut hand = String::new();
+            while hand.is_empty() || hands.contains(&hand) {
+                hand = (0..5).map(|_| labels.choose(rng).unwrap()).collect();
+            }
+            hands.insert(hand.clone());
+            format!("{hand} {}", rng.gen_range(1..=1000))
+        })
+        .join("\n")
+}
+
+/// Day 8 - Ghost network, where each ghost walks a loop through its own Z node.
+///
+/// Both branches of a node lead to the same place, and the node after Z is
+/// the node after A, so every ghost reaches Z on a fixed period. Node names
+/// are three letters, so the paths are shortened to share out the names.
+pub fn day_8(size: usize, rng: &mut StdRng) -> Result<String, Report> {
+    let directions = (0..size).map(|_| ['L', 'R'].choose(rng).unwrap()).join("");
+
+    // three letter node names, that don't end with A or Z
+    let letters = "BCDEFGHIJKLMNOPQRSTUVWXY".chars().collect_vec();
+    let mut names = letters.iter().cartesian_product(&letters).cartesian_product(&letters);
+
+    let mut prefixes = letters.iter().cartesian_product(&letters);
+
+    let ghosts = 1 + size / 10;
+    let length = size.min(letters.len().pow(3) / ghosts);
+    let mut nodes = Vec::new();
+
+    for ghost in 0..ghosts {
+        let (start, end) = match (ghost, prefixes.next()) {
+            (0, _) => ("AAA".to_string(), "ZZZ".to_string()),
+            (_, Some((a, b))) => (format!("{a}{b}A"), format!("{a}{b}Z")),
+            _ => return Err(eyre!("Ran out of node names for {ghosts} ghosts.")),
+        };
+
+        let mut path = vec![start];
+        for _ in 0..rng.gen_range(1..=length) {
+            let ((a, b), c) = names.next().ok_or_else(|| eyre!("Ran out of node names."))?;
+            path.push(format!("{a}{b}{c}"));
+        }
+        path.push(end);
+
+        path.iter().tuple_windows().for_each(|(from, to)| {
+            nodes.push(format!("{from} = ({to}, {to})"));
+        });
+        nodes.push(format!(
+            "{} = ({}, {})",
+            path[path.len() - 1],
+            path[1],
+            path[1]
+        ));
+    }
+
+    nodes.shuffle(rng);
+    Ok(format!("{directions}\n\n{}", nodes.join("\n")))
+}
+
+/// Day 9 - OASIS sequences sampled from small polynomials.
+pub fn day_9(size: usize, rng: &mut StdRng) -> String {
+    (0..size)
+        .map(|_| {
+            let length = rng.gen_range(6..=21);
+            let degree = rng.gen_range(0..=4);
+            let coefficients = (0..=degree).map(|_| rng.gen_range(-5..=5)).collect_vec();
+            (0..length as isize)
+                .map(|x| coefficients.iter().rev().fold(0, |value, c| value * x + c))
+                .join(" ")
+        })
+        .join("\n")
+}
+
+/// Day 10 - Pipe map with a single loop through 'S', surrounded by junk pipes.
+///
+/// The loop is the boundary of a random region of cells, grown without holes
+/// or cells touching only at the corners so that the boundary stays simple.
+/// The pipes sit on the corners of those cells.
+pub fn day_10(size: usize, rng: &mut StdRng) -> String {
+    let mut region = vec![vec![false; size]; size];
+    let is_region = |region: &Vec<Vec<bool>>, x: isize, y: isize| {
+        x >= 0
+            && y >= 0
+            && (x as usize) < size
+            && (y as usize) < size
+            && region[y as usize][x as usize]
+    };
+    region[size / 2][size / 2] = true;
+
+    // grow the region one cell at a time
+    for _ in 0..size * size * 2 {
+        let (x, y) = (rng.gen_range(0..size), rng.gen_range(0..size));
+        let (xi, yi) = (x as isize, y as isize);
+        let touches = [(xi - 1, yi), (xi + 1, yi), (xi, yi - 1), (xi, yi + 1)]
+            .iter()
+            .any(|(x, y)| is_region(&region, *x, *y));
+        if region[y][x] || !touches {
+            continue;
+        }
+
+        // don't let the region touch itself diagonally
+        region[y][x] = true;
+        let pinched = (xi - 1..=xi).cartesian_product(yi - 1..=yi).any(|(x, y)| {
+            let (a, b) = (is_region(&region, x, y), is_region(&region, x + 1, y));
+            let (c, d) = (
+                is_region(&region, x, y + 1),
+                is_region(&region, x + 1, y + 1),
+            );
+            (a && d && !b && !c) || (b && c && !a && !d)
+        });
+        if pinched {
+            region[y][x] = false;
+        }
+    }
+
+    // fill in holes, anything that the outside can't reach
+    let mut outside = vec![vec![false; size]; size];
+    let mut queue = (0..size)
+        .flat_map(|i| [(i, 0), (i, size - 1), (0, i), (size - 1, i)])
+        .filter(|(x, y)| !region[*y][*x])
+        .collect_vec();
+    while let Some((x, y)) = queue.pop() {
+        if outside[y][x] {
+            continue;
+        }
+        outside[y][x] = true;
+        let (xi, yi) = (x as isize, y as isize);
+        [(xi - 1, yi), (xi + 1, yi), (xi, yi - 1), (xi, yi + 1)]
+            .into_iter()
+            .filter(|(x, y)| *x >= 0 && *y >= 0 && (*x as usize) < size && (*y as usize) < size)
+            .map(|(x, y)| (x as usize, y as usize))
+            .filter(|(x, y)| !region[*y][*x])
+            .for_each(|c| queue.push(c));
+    }
+    (0..size).cartesian_product(0..size).for_each(|(x, y)| region[y][x] = !outside[y][x]);
+
+    // place pipes on the cell corners, with a margin of one tile around the map
+    let junk = ['.', '.', '.', '|', '-', 'L', 'J', '7', 'F'];
+    let mut pipes = Vec::new();
+    let mut tiles = (0..size + 3)
+        .map(|y| {
+            (0..size + 3)
+                .map(|x| {
+                    let (x, y) = (x as isize - 1, y as isize - 1);
+                    // a cell edge is part of the loop when the cells on either side differ
+                    let n = is_region(&region, x - 1, y - 1) != is_region(&region, x, y - 1);
+                    let s = is_region(&region, x - 1, y) != is_region(&region, x, y);
+                    let w = is_region(&region, x - 1, y - 1) != is_region(&region, x - 1, y);
+                    let e = is_region(&region, x, y - 1) != is_region(&region, x, y);
+                    let pipe = match (n, e, s, w) {
+                        (true, false, true, false) => '|',
+                        (false, true, false, true) => '-',
+                        (true, true, false, false) => 'L',
+                        (true, false, false, true) => 'J',
+                        (false, false, true, true) => '7',
+                        (false, true, true, false) => 'F',
+                        _ => return *junk.choose(rng).unwrap(),
+                    };
+                    pipes.push(((x + 1) as usize, (y + 1) as usize));
+                    pipe
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+
+    // hide the start under one of the loop pipes, and make sure only the
+    // loop connects to it
+    let (x, y) = *pipes.choose(rng).unwrap();
+    tiles[y][x] = 'S';
+    [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].into_iter().for_each(|(xn, yn)| {
+        if !pipes.contains(&(xn, yn)) {
+            tiles[yn][xn] = '.';
+        }
+    });
+
+    tiles.into_iter().map(|row| row.into_iter().join("")).join("\n")
+}
+
+/// Day 11 - Square galaxy map, with some rows and columns left empty.
+pub fn day_11(size: usize, rng: &mut StdRng) -> String {
+    let size = size.max(2);
+    let empty_x = (0..size).filter(|_| rng.gen_bool(0.2)).collect_vec();
+    let empty_y = (0..size).filter(|_| rng.gen_bool(0.2)).collect_vec();
+
+    let mut tiles = (0..size)
+        .map(|y| {
+            (0..size)
+                .map(|x| {
+                    let empty = empty_x.contains(&x) || empty_y.contains(&y);
+                    match !empty && rng.gen_bool(0.15) {
+                        true => '#',
+                        false => '.',
+                    }
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+
+    // need at least one pair of galaxies
+    tiles[0][0] = '#';
+    tiles[size - 1][size - 1] = '#';
+
+    tiles.into_iter().map(|row| row.into_iter().join("")).join("\n")
+}
+
+/// Day 12 - Spring records, made by hiding parts of a valid arrangement.
+pub fn day_12(size: usize, rng: &mut StdRng) -> String {
+    (0..size)
+        .map(|_| {
+            let damage = (0..rng.gen_range(1..=5)).map(|_| rng.gen_range(1..=4)).collect_vec();
+
+            let mut springs = ".".repeat(rng.gen_range(0..=2));
+            damage.iter().enumerate().for_each(|(i, d)| {
+                if i > 0 {
+                    springs.push_str(&".".repeat(rng.gen_range(1..=3)));
+                }
+                springs.push_str(&"#".repeat(*d));
+            });
+            springs.push_str(&".".repeat(rng.gen_range(0..=2)));
+
+            let springs: String =
+                springs.chars().map(|c| if rng.gen_bool(0.5) { '?' } else { c }).collect();
+            format!("{springs} {}", damage.iter().join(","))
+        })
+        .join("\n")
+}
+
+#[test]
+fn solvable() -> Result<(), Report> {
+    use crate::day::{self, Part};
+
+    for day in 1..=12 {
+        let day = day.to_string();
+        for seed in 0..3 {
+            let input = generate(&day, 10, seed)?;
+            day::solve(&day, &input, &Part::Part1)?;
+            day::solve(&day, &input, &Part::Part2)?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn reproducible() -> Result<(), Report> {
+    for day in 1..=12 {
+        let day = day.to_string();
+        assert_eq!(generate(&day, 20, 7)?, generate(&day, 20, 7)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn large() -> Result<(), Report> {
+    // the ghosts share out the three letter node names
+    let input = generate("8", 1000, 1)?;
+    let nodes = input.split("\n\n").nth(1).unwrap_or_default().split('\n');
+    assert!(nodes.map(|n| &n[0..3]).all_unique());
+    assert!(generate("8", 10_000, 1).is_err());
+    Ok(())
+}
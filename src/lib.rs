@@ -1,40 +1,191 @@
 pub mod day;
 pub mod game;
+pub mod generate;
+pub mod minimize;
 pub mod poker;
+pub mod reference;
 pub mod utils;
 
 use crate::day::*;
-use clap::{Parser, ValueEnum};
+use crate::utils::table::{Aggregate, Join, Layout, Order, Table};
+use clap::builder::RangedU64ValueParser;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use color_eyre::eyre::{eyre, Report, Result};
-use log::info;
+use itertools::Itertools;
+use log::{error, info, warn};
 use std::default::Default;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
 // Puzzle dispatcher
 // ----------------------------------------------------------------------------
 
-/// Run puzzle day and part.
+/// Run puzzle day and part, or one of the subcommands.
 pub fn run(args: &Cli) -> Result<(), Report> {
-    info!("Day {}", args.day);
-
-    let part = <Part as FromStr>::from_str(&args.part)?;
-
-    match args.day.as_ref() {
-        "1" => day_1::run(&part)?,
-        "2" => day_2::run(&part)?,
-        "3" => day_3::run(&part)?,
-        "4" => day_4::run(&part)?,
-        "5" => day_5::run(&part)?,
-        "6" => day_6::run(&part)?,
-        "7" => day_7::run(&part)?,
-        "8" => day_8::run(&part)?,
-        "9" => day_9::run(&part)?,
-        "10" => day_10::run(&part)?,
-        "11" => day_11::run(&part)?,
-        "12" => day_12::run(&part)?,
-        _ => return Err(eyre!("Day {} is not implemented yet.", args.day)),
+    utils::table::set_output_format(args.format);
+    let layout = match args.fit {
+        true => Layout::terminal(),
+        false => Layout::default(),
     };
+    utils::table::set_output_layout(Layout {
+        width: args.width.or(layout.width),
+        cell_width: args.cell_width,
+        rows: args.max_rows,
+        page: args.page.saturating_sub(1),
+    });
+
+    if let Some(command) = &args.command {
+        return match command {
+            Command::Generate(args) => {
+                let input = generate::generate(&args.day, args.size, args.seed)?;
+                println!("{input}");
+                Ok(())
+            }
+            Command::Differential(args) => {
+                match reference::differential(&args.day, args.size, args.seeds)? {
+                    Some(disagreement) => {
+                        error!("{disagreement}");
+                        match &args.output {
+                            Some(path) => std::fs::write(path, &disagreement.input)?,
+                            None => println!("{}", disagreement.input),
+                        }
+                        Err(eyre!(
+                            "Solution and reference disagree on day {}.",
+                            args.day
+                        ))
+                    }
+                    None => {
+                        info!("No disagreements in {} inputs.", args.seeds);
+                        Ok(())
+                    }
+                }
+            }
+            Command::Minimize(args) => {
+                let input = utils::read_to_string(&args.input.to_string_lossy())?;
+                let part = args.part.as_deref().map(<Part as FromStr>::from_str).transpose()?;
+                let variants = args.variants.iter().map(|v| day::variant(&args.day, Some(v)));
+                let variants = variants.collect::<Result<Vec<_>, Report>>()?;
+                let failure =
+                    minimize::Failure::new(&args.day, part, args.predicate, &variants, &input)?;
+                let minimized = minimize::minimize(&input, &failure);
+                match &args.output {
+                    Some(path) => std::fs::write(path, minimized)?,
+                    None => println!("{minimized}"),
+                }
+                Ok(())
+            }
+            Command::Compare(args) => {
+                let path = match &args.input {
+                    Some(path) => path.to_string_lossy().to_string(),
+                    None => format!("data/day_{}.txt", args.day),
+                };
+                let input = utils::read_to_string(&path)?;
+                let parts = match &args.part {
+                    Some(part) => vec![<Part as FromStr>::from_str(part)?],
+                    None => vec![Part::Part1, Part::Part2],
+                };
+                let (table, agree) = day::compare(&args.day, &input, &parts)?;
+                println!("{}", table.render()?);
+                match agree {
+                    true => Ok(()),
+                    false => Err(eyre!("Variants of day {} disagree.", args.day)),
+                }
+            }
+            Command::Table(args) => {
+                let mut table = Table::from_path(&args.input)?;
+                let on = args.on.iter().map(String::as_str).collect_vec();
+                for path in &args.concat {
+                    table = table.concat(&Table::from_path(path)?);
+                }
+                if let Some(path) = &args.join {
+                    table = table.join(&Table::from_path(path)?, &on, args.how)?;
+                }
+                if let Some(path) = &args.diff {
+                    table = table.diff(&Table::from_path(path)?, &on)?;
+                }
+                for expr in &args.filter {
+                    table = table.filter_expr(expr)?;
+                }
+                if !args.group_by.is_empty() || !args.aggregate.is_empty() {
+                    // count doesn't need a column, any will do
+                    let first = table.headers.first().cloned().unwrap_or_default();
+                    let aggregates = args
+                        .aggregate
+                        .iter()
+                        .map(|a| match a.split_once(':') {
+                            Some((aggregate, header)) => Ok((
+                                header,
+                                Aggregate::from_str(aggregate, true).map_err(|e| eyre!(e))?,
+                            )),
+                            None if a == "count" => Ok((first.as_str(), Aggregate::Count)),
+                            None => Err(eyre!("Aggregate '{a}' is not like sum:time.")),
+                        })
+                        .collect::<Result<Vec<_>, Report>>()?;
+                    let keys = args.group_by.iter().map(String::as_str).collect_vec();
+                    table = table.group_by(&keys, &aggregates)?;
+                }
+                if !args.sort.is_empty() {
+                    let keys = args
+                        .sort
+                        .iter()
+                        .map(|k| match k.strip_prefix('-') {
+                            Some(k) => (k, Order::Descending),
+                            None => (k.as_str(), Order::Ascending),
+                        })
+                        .collect_vec();
+                    table = table.sort(&keys)?;
+                }
+                if !args.select.is_empty() {
+                    table = table.select(&args.select.iter().map(String::as_str).collect_vec())?;
+                }
+                if args.transpose {
+                    table = table.transpose();
+                }
+                println!("{}", table.render()?);
+                Ok(())
+            }
+            Command::Lint(args) => {
+                let path = match &args.input {
+                    Some(path) => path.to_string_lossy().to_string(),
+                    None => format!("data/day_{}.txt", args.day),
+                };
+                let input = utils::read_to_string(&path)?;
+                let (table, hold) = day::lint(&args.day, &input)?;
+                println!("{}", table.render()?);
+                match hold {
+                    true => Ok(()),
+                    false => Err(eyre!("Input violates assumptions of day {}.", args.day)),
+                }
+            }
+        };
+    }
+
+    // clap requires the day and part when there is no subcommand
+    let (day, part) = (
+        args.day.clone().unwrap_or_default(),
+        args.part.clone().unwrap_or_default(),
+    );
+    let part = <Part as FromStr>::from_str(&part)?;
+    let variant = day::variant(&day, args.variant.as_deref())?;
+    info!("Day {day} ({})", variant.name);
+
+    let input = utils::read_to_string(&format!("data/day_{day}.txt"))?;
+
+    // the answer may still be right, so only warn about violated assumptions
+    let assumptions = match args.lint {
+        true => day::assumptions(&day)?,
+        false => &[],
+    };
+    for assumption in assumptions {
+        if let Err(e) = utils::catch_panic(|| (assumption.check)(&input)) {
+            warn!("Input violates assumption that {}: {e}", assumption.name);
+        }
+    }
+
+    let result = (variant.solve)(&input, &part)?;
+    info!("Answer: {result}");
 
     Ok(())
 }
@@ -55,20 +206,240 @@ pub fn run(args: &Cli) -> Result<(), Report> {
 #[clap(author, version)]
 #[clap(verbatim_doc_comment)]
 #[clap(arg_required_else_help = true)]
+#[clap(subcommand_negates_reqs = true)]
 pub struct Cli {
     /// Output verbosity level.
-    #[clap(short = 'v', long)]
+    #[clap(short = 'v', long, global = true)]
     #[clap(value_enum, default_value_t = Verbosity::default())]
     #[clap(hide_possible_values = false)]
     pub verbosity: Verbosity,
 
+    /// Format of printed tables.
+    #[clap(short = 'f', long, global = true)]
+    #[clap(value_enum, default_value_t = utils::table::Format::default())]
+    pub format: utils::table::Format,
+
+    /// Fit printed tables to the terminal width.
+    #[clap(long, global = true)]
+    pub fit: bool,
+
+    /// Maximum width of printed tables, wider tables are split into chunks of columns.
+    #[clap(long, global = true)]
+    pub width: Option<usize>,
+
+    /// Maximum width of a table cell, longer cells are cut short.
+    #[clap(long, global = true)]
+    pub cell_width: Option<usize>,
+
+    /// Number of table rows per page, only one page is printed.
+    #[clap(long, global = true)]
+    #[clap(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub max_rows: Option<usize>,
+
+    /// Page of table rows to print, when paging with --max-rows.
+    #[clap(long, global = true, default_value_t = 1)]
+    #[clap(value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub page: usize,
+
     /// Puzzle day number.
     #[clap(short = 'd', long, required = true)]
-    pub day: String,
+    pub day: Option<String>,
 
     /// Puzzle part.
     #[clap(short = 'p', long, required = true)]
-    pub part: String,
+    pub part: Option<String>,
+
+    /// Solution variant, otherwise the default variant of the day.
+    #[clap(long)]
+    pub variant: Option<String>,
+
+    /// Check the input against the day's assumptions before solving, which
+    /// can be slow.
+    #[clap(long)]
+    pub lint: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// Check that the puzzle solving args aren't given with a subcommand.
+    ///
+    /// clap can only make every arg conflict with subcommands, which would
+    /// also reject global args like `--format` before the subcommand.
+    pub fn check(&self) -> Result<(), clap::Error> {
+        let solve_args = [
+            ("--day", self.day.is_some()),
+            ("--part", self.part.is_some()),
+            ("--variant", self.variant.is_some()),
+            ("--lint", self.lint),
+        ];
+        match (&self.command, solve_args.iter().find(|(_, given)| *given)) {
+            (Some(_), Some((arg, _))) => Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                format!("the argument '{arg}' cannot be used with a subcommand"),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Subcommands
+// -----------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Command {
+    /// Generate a random puzzle input.
+    Generate(GenerateArgs),
+    /// Compare a solution to the reference solution on generated inputs.
+    Differential(DifferentialArgs),
+    /// Shrink an input to the smallest version that still fails.
+    Minimize(MinimizeArgs),
+    /// Run every solution variant of a day, and compare answers and timings.
+    Compare(CompareArgs),
+    /// Check an input against the assumptions the solutions rely on.
+    Lint(LintArgs),
+    /// Query a CSV, TSV or markdown table.
+    Table(TableArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct GenerateArgs {
+    /// Puzzle day number.
+    #[clap(short = 'd', long, required = true)]
+    pub day: String,
+
+    /// Input size (number of lines, or grid side length).
+    #[clap(short = 's', long, default_value_t = 10)]
+    pub size: usize,
+
+    /// Random seed.
+    #[clap(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct DifferentialArgs {
+    /// Puzzle day number.
+    #[clap(short = 'd', long, required = true)]
+    pub day: String,
+
+    /// Input size (number of lines, or grid side length).
+    #[clap(short = 's', long, default_value_t = 10)]
+    pub size: usize,
+
+    /// Number of inputs to generate, using seeds 0 to N-1.
+    #[clap(long, default_value_t = 100)]
+    pub seeds: u64,
+
+    /// Write the first input that disagrees to this file, instead of stdout.
+    #[clap(short = 'o', long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct MinimizeArgs {
+    /// Puzzle day number.
+    #[clap(short = 'd', long, required = true)]
+    pub day: String,
+
+    /// Puzzle input file that fails.
+    #[clap(short = 'i', long, required = true)]
+    pub input: PathBuf,
+
+    /// How the input fails.
+    #[clap(long, value_enum, default_value_t = minimize::Predicate::Disagree)]
+    pub predicate: minimize::Predicate,
+
+    /// Puzzle part to check, otherwise either part can fail.
+    #[clap(short = 'p', long)]
+    pub part: Option<String>,
+
+    /// Solution variants to check, the first is the one that errors.
+    #[clap(long, value_delimiter = ',', default_value = "default,reference")]
+    pub variants: Vec<String>,
+
+    /// Write the minimized input to this file, instead of stdout.
+    #[clap(short = 'o', long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct CompareArgs {
+    /// Puzzle day number.
+    #[clap(short = 'd', long, required = true)]
+    pub day: String,
+
+    /// Puzzle input file, otherwise the day's data file.
+    #[clap(short = 'i', long)]
+    pub input: Option<PathBuf>,
+
+    /// Puzzle part, otherwise both parts.
+    #[clap(short = 'p', long)]
+    pub part: Option<String>,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct LintArgs {
+    /// Puzzle day number.
+    #[clap(short = 'd', long, required = true)]
+    pub day: String,
+
+    /// Puzzle input file, otherwise the day's data file.
+    #[clap(short = 'i', long)]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct TableArgs {
+    /// Table file (.csv, .tsv or .md).
+    #[clap(short = 'i', long, required = true)]
+    pub input: PathBuf,
+
+    /// Append the rows of other tables, matching columns by header.
+    #[clap(long)]
+    pub concat: Vec<PathBuf>,
+
+    /// Join with another table on the '--on' columns.
+    #[clap(long, requires = "on")]
+    pub join: Option<PathBuf>,
+
+    /// Which rows to keep when joining.
+    #[clap(long, value_enum, default_value_t = Join::Inner)]
+    pub how: Join,
+
+    /// Show the rows added, removed or changed in another table, matched on the '--on' columns.
+    #[clap(long, requires = "on", conflicts_with = "join")]
+    pub diff: Option<PathBuf>,
+
+    /// Key columns to join or diff on.
+    #[clap(long, value_delimiter = ',')]
+    pub on: Vec<String>,
+
+    /// Keep rows matching an expression like 'time>10' or 'day~^1' (regex), can repeat.
+    #[clap(long)]
+    pub filter: Vec<String>,
+
+    /// Group rows by these columns.
+    #[clap(long, value_delimiter = ',')]
+    pub group_by: Vec<String>,
+
+    /// Aggregate columns of each group, like 'count' or 'mean:time'.
+    #[clap(long, value_delimiter = ',')]
+    pub aggregate: Vec<String>,
+
+    /// Sort by these columns, prefix with '-' for descending.
+    #[clap(long, value_delimiter = ',', allow_hyphen_values = true)]
+    pub sort: Vec<String>,
+
+    /// Keep only these columns, in this order.
+    #[clap(long, value_delimiter = ',')]
+    pub select: Vec<String>,
+
+    /// Swap rows and columns.
+    #[clap(long)]
+    pub transpose: bool,
 }
 
 // -----------------------------------------------------------------------------
@@ -91,3 +462,27 @@ impl std::fmt::Display for Verbosity {
         write!(f, "{lowercase}")
     }
 }
+
+#[test]
+fn cli() -> Result<(), Report> {
+    // global args can come before or after the subcommand
+    for args in [
+        "aoc -v debug generate -d 1",
+        "aoc --format csv compare -d 4",
+        "aoc compare -d 4 --fit --max-rows 5 --page 2",
+    ] {
+        let cli = Cli::try_parse_from(args.split(' '))?;
+        cli.check()?;
+    }
+    let cli = Cli::try_parse_from("aoc --day 1 generate -d 1".split(' '))?;
+    assert_eq!(
+        cli.check().map_err(|e| e.kind()),
+        Err(ErrorKind::ArgumentConflict)
+    );
+    assert!(Cli::try_parse_from("aoc --day 1".split(' ')).is_err());
+
+    // pages count from 1, and hold at least one row
+    assert!(Cli::try_parse_from("aoc table -i t.csv --max-rows 0".split(' ')).is_err());
+    assert!(Cli::try_parse_from("aoc table -i t.csv --page 0".split(' ')).is_err());
+    Ok(())
+}
@@ -7,6 +7,7 @@ fn main() -> Result<(), Report> {
 
     // Parse CLI Parameters
     let args = advent_of_code_2023::Cli::parse();
+    args.check().unwrap_or_else(|e| e.exit());
 
     // initialize color_eyre crate for colorized logs
     color_eyre::install()?;
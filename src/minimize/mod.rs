@@ -0,0 +1,234 @@
+use crate::day::{Part, Variant};
+use crate::utils;
+
+use clap::ValueEnum;
+use color_eyre::eyre::{eyre, Report, Result};
+use itertools::Itertools;
+use log::{debug, info};
+
+// ----------------------------------------------------------------------------
+// Predicates
+// ----------------------------------------------------------------------------
+
+/// What counts as a failing input while minimizing.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Predicate {
+    /// Two solution variants both answer, but differently.
+    Disagree,
+    /// A solution variant errors (or panics) with the same message as the original input.
+    Error,
+}
+
+/// A failing predicate, bound to the variants and parts to check, and the original failure.
+pub struct Failure {
+    pub parts: Vec<Part>,
+    pub predicate: Predicate,
+    pub variants: Vec<Variant>,
+    message: Option<String>,
+}
+
+impl Failure {
+    /// Check that an input fails the predicate, and remember how it fails.
+    ///
+    /// The first variant is checked for errors, and the first two variants
+    /// are checked for disagreement.
+    pub fn new(
+        day: &str,
+        part: Option<Part>,
+        predicate: Predicate,
+        variants: &[Variant],
+        input: &str,
+    ) -> Result<Self, Report> {
+        let required = match predicate {
+            Predicate::Disagree => 2,
+            Predicate::Error => 1,
+        };
+        if variants.len() < required {
+            return Err(eyre!(
+                "The {predicate:?} predicate needs {required} variant(s)."
+            ));
+        }
+
+        let parts = match part {
+            Some(part) => vec![part],
+            None => vec![Part::Part1, Part::Part2],
+        };
+
+        let mut failure = Failure {
+            parts,
+            predicate,
+            variants: variants.to_vec(),
+            message: None,
+        };
+
+        // errors must keep the same message, so we don't minimize into a different failure
+        if predicate == Predicate::Error {
+            let solve = failure.variants[0].solve;
+            failure.message = failure.parts.iter().find_map(|part| {
+                utils::catch_panic(|| solve(input, part)).err().map(|e| e.to_string())
+            });
+        }
+
+        match failure.fails(input) {
+            true => Ok(failure),
+            false => Err(eyre!(
+                "The input does not fail the {predicate:?} predicate for day {day}."
+            )),
+        }
+    }
+
+    /// Check if an input fails the predicate on any of the parts.
+    pub fn fails(&self, input: &str) -> bool {
+        self.parts.iter().any(|part| {
+            let observed = utils::catch_panic(|| (self.variants[0].solve)(input, part));
+            match self.predicate {
+                Predicate::Disagree => {
+                    let expected = utils::catch_panic(|| (self.variants[1].solve)(input, part));
+                    matches!((expected, observed), (Ok(e), Ok(o)) if e != o)
+                }
+                Predicate::Error => match (&self.message, observed) {
+                    (Some(message), Err(e)) => e.to_string() == *message,
+                    _ => false,
+                },
+            }
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Minimization
+// ----------------------------------------------------------------------------
+
+/// Shrink an input to the smallest version that still fails.
+///
+/// Applies delta debugging to the blank line separated blocks, then the lines,
+/// then the columns (if the input is a grid), and repeats until nothing more
+/// can be removed.
+pub fn minimize(input: &str, failure: &Failure) -> String {
+    let mut input = input.to_string();
+
+    loop {
+        let before = input.len();
+
+        // blocks
+        let blocks = input.split("\n\n").collect_vec();
+        input = ddmin(&blocks, |b| failure.fails(&b.join("\n\n"))).join("\n\n");
+        debug!("Minimized to {} blocks.", input.split("\n\n").count());
+
+        // lines
+        let lines = input.split('\n').collect_vec();
+        input = ddmin(&lines, |l| failure.fails(&l.join("\n"))).join("\n");
+        debug!("Minimized to {} lines.", input.split('\n').count());
+
+        // grid columns, removed from every row at once
+        let rows = input.split('\n').map(|l| l.chars().collect_vec()).collect_vec();
+        let width = rows[0].len();
+        if rows.len() > 1 && rows.iter().all(|r| r.len() == width) {
+            let join = |columns: &[usize]| {
+                rows.iter().map(|r| columns.iter().map(|x| r[*x]).collect::<String>()).join("\n")
+            };
+            let columns = ddmin(&(0..width).collect_vec(), |c| failure.fails(&join(c)));
+            input = join(&columns);
+            debug!("Minimized to {} columns.", columns.len());
+        }
+
+        if input.len() == before {
+            break;
+        }
+    }
+
+    info!(
+        "Minimized input to {} lines, {} characters.",
+        input.split('\n').count(),
+        input.len()
+    );
+    input
+}
+
+/// Delta debugging, find a minimal subset of units that still fails.
+///
+/// Split the units into `n` chunks, and try to keep just one chunk, or
+/// remove just one chunk. If neither fails, split into finer chunks.
+///
+/// Zeller and Hildebrandt (2002), Simplifying and Isolating Failure-Inducing Input.
+pub fn ddmin<T: Clone>(units: &[T], mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut units = units.to_vec();
+    let mut n = 2;
+
+    while units.len() >= 2 {
+        let chunk = (units.len() + n - 1) / n;
+        let chunks = units.chunks(chunk).map(|c| c.to_vec()).collect_vec();
+
+        // try each chunk on its own
+        if let Some(subset) = chunks.iter().find(|c| fails(c)) {
+            units = subset.clone();
+            n = 2;
+            continue;
+        }
+
+        // try removing each chunk
+        let complement = (0..chunks.len()).find_map(|i| {
+            let complement = chunks
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .flat_map(|(_, c)| c.clone())
+                .collect_vec();
+            fails(&complement).then_some(complement)
+        });
+        if let Some(complement) = complement {
+            units = complement;
+            n = (n - 1).max(2);
+            continue;
+        }
+
+        // increase granularity
+        if n >= units.len() {
+            break;
+        }
+        n = (n * 2).min(units.len());
+    }
+
+    units
+}
+
+#[test]
+fn delta_debugging() {
+    // fails when both 3 and 7 are present
+    let units = (0..20).collect_vec();
+    let observed = ddmin(&units, |u| u.contains(&3) && u.contains(&7));
+    assert_eq!(observed, vec![3, 7]);
+}
+
+#[test]
+fn disagreement() -> Result<(), Report> {
+    use crate::{generate, reference};
+
+    // a broken variant, that miscounts game 7
+    let broken = |input: &str, part: &Part| {
+        let result = reference::day_2(input, part)?;
+        Ok(result + input.matches("Game 7:").count())
+    };
+    let variants = [
+        Variant {
+            name: "broken",
+            solve: broken,
+        },
+        Variant {
+            name: "reference",
+            solve: reference::day_2,
+        },
+    ];
+    let input = generate::generate("2", 10, 4)?;
+    let failure = Failure::new(
+        "2",
+        Some(Part::Part2),
+        Predicate::Disagree,
+        &variants,
+        &input,
+    )?;
+    let minimized = minimize(&input, &failure);
+    assert!(failure.fails(&minimized));
+    assert!(minimized.starts_with("Game 7:") && !minimized.contains('\n'));
+    Ok(())
+}
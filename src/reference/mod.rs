@@ -0,0 +1,637 @@
+use crate::day::{self, Part};
+use crate::generate;
+use crate::utils;
+
+use color_eyre::eyre::{eyre, Report, Result};
+use itertools::Itertools;
+use log::debug;
+use std::collections::BTreeMap;
+
+// ----------------------------------------------------------------------------
+// Reference dispatcher
+// ----------------------------------------------------------------------------
+
+/// Solve a puzzle day and part with the slow reference solution.
+///
+/// The reference solutions favor being obviously correct over being fast,
+/// so they are only suitable for small (generated) inputs.
+pub fn solve(day: &str, input: &str, part: &Part) -> Result<usize, Report> {
+    let result = match day {
+        "1" => day_1(input, part)?,
+        "2" => day_2(input, part)?,
+        "3" => day_3(input, part)?,
+        "4" => day_4(input, part)?,
+        "5" => day_5(input, part)?,
+        "6" => day_6(input, part)?,
+        "7" => day_7(input, part)?,
+        "8" => day_8(input, part)?,
+        "9" => day_9(input, part)?,
+        "10" => day_10(input, part)?,
+        "11" => day_11(input, part)?,
+        "12" => day_12(input, part)?,
+        _ => return Err(eyre!("Day {day} does not have a reference solution yet.")),
+    };
+
+    Ok(result)
+}
+
+// ----------------------------------------------------------------------------
+// Differential testing
+// ----------------------------------------------------------------------------
+
+/// A generated input where the solution and the reference solution disagree.
+#[derive(Clone, Debug)]
+pub struct Disagreement {
+    pub day: String,
+    pub part: Part,
+    pub seed: u64,
+    pub input: String,
+    pub expected: Result<usize, String>,
+    pub observed: Result<usize, String>,
+}
+
+impl std::fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let outcome = |o: &Result<usize, String>| match o {
+            Ok(answer) => answer.to_string(),
+            Err(e) => format!("error ({e})"),
+        };
+        write!(
+            f,
+            "Day {} {:?} with seed {}: expected {}, observed {}",
+            self.day,
+            self.part,
+            self.seed,
+            outcome(&self.expected),
+            outcome(&self.observed)
+        )
+    }
+}
+
+/// Feed generated inputs to both the solution and the reference solution,
+/// returning the first input where they disagree.
+///
+/// Errors and panics count as an outcome, so a solution that crashes where
+/// the reference does not is also a disagreement.
+pub fn differential(day: &str, size: usize, seeds: u64) -> Result<Option<Disagreement>, Report> {
+    for seed in 0..seeds {
+        let input = generate::generate(day, size, seed)?;
+
+        for part in [Part::Part1, Part::Part2] {
+            let expected = utils::catch_panic(|| solve(day, &input, &part));
+            let observed = utils::catch_panic(|| day::solve(day, &input, &part));
+            debug!("Seed {seed} {part:?}: expected {expected:?}, observed {observed:?}");
+
+            let (expected, observed) = (
+                expected.map_err(|e| e.to_string()),
+                observed.map_err(|e| e.to_string()),
+            );
+            if expected != observed {
+                let disagreement = Disagreement {
+                    day: day.to_string(),
+                    part,
+                    seed,
+                    input,
+                    expected,
+                    observed,
+                };
+                return Ok(Some(disagreement));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// ----------------------------------------------------------------------------
+// Reference solutions
+// ----------------------------------------------------------------------------
+
+/// Day 1 - Scan every position of the line for a digit (or digit name).
+pub fn day_1(input: &str, part: &Part) -> Result<usize, Report> {
+    let names = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    let digit_at = |line: &str, i: usize| {
+        let c = line.as_bytes()[i] as char;
+        if let Some(d) = c.to_digit(10) {
+            return Some(d as usize);
+        }
+        match *part {
+            Part::Part1 => None,
+            Part::Part2 => names.iter().position(|n| line[i..].starts_with(n)).map(|d| d + 1),
+        }
+    };
+
+    input
+        .split('\n')
+        .map(|line| {
+            let first = (0..line.len()).find_map(|i| digit_at(line, i));
+            let last = (0..line.len()).rev().find_map(|i| digit_at(line, i));
+            match (first, last) {
+                (Some(first), Some(last)) => Ok(first * 10 + last),
+                _ => Err(eyre!("No digits in line: {line}")),
+            }
+        })
+        .sum()
+}
+
+/// Day 2 - Track the most cubes of each color seen in every game.
+pub fn day_2(input: &str, part: &Part) -> Result<usize, Report> {
+    let mut result = 0;
+
+    for line in input.split('\n') {
+        let (game, draws) = line.split_once(": ").ok_or_else(|| eyre!("Malformed game: {line}"))?;
+        let id: usize = game.trim_start_matches("Game ").parse()?;
+
+        let mut most: BTreeMap<&str, usize> =
+            [("red", 0), ("green", 0), ("blue", 0)].into_iter().collect();
+        for cubes in draws.split("; ").flat_map(|draw| draw.split(", ")) {
+            let (count, color) =
+                cubes.split_once(' ').ok_or_else(|| eyre!("Malformed cubes: {cubes}"))?;
+            let count: usize = count.parse()?;
+            let most = most.get_mut(color).ok_or_else(|| eyre!("Unknown color: {color}"))?;
+            *most = count.max(*most);
+        }
+
+        result += match *part {
+            Part::Part1 => match most["red"] <= 12 && most["green"] <= 13 && most["blue"] <= 14 {
+                true => id,
+                false => 0,
+            },
+            Part::Part2 => most.values().product(),
+        };
+    }
+
+    Ok(result)
+}
+
+/// Day 3 - Check the whole box around every number in the schematic.
+pub fn day_3(input: &str, part: &Part) -> Result<usize, Report> {
+    let tiles = input.split('\n').map(|l| l.chars().collect_vec()).collect_vec();
+
+    // find every number as (value, y, x_start, x_end)
+    let mut numbers = Vec::new();
+    for (y, row) in tiles.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            if row[x].is_ascii_digit() {
+                let start = x;
+                while x < row.len() && row[x].is_ascii_digit() {
+                    x += 1;
+                }
+                let value: usize = row[start..x].iter().collect::<String>().parse()?;
+                numbers.push((value, y, start, x - 1));
+            } else {
+                x += 1;
+            }
+        }
+    }
+
+    // is the tile (x, y) within the box around a number
+    let around = |(_, ny, start, end): &(usize, usize, usize, usize), x: usize, y: usize| {
+        y + 1 >= *ny && y <= ny + 1 && x + 1 >= *start && x <= end + 1
+    };
+
+    let symbols = tiles
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (x, y, *c)))
+        .filter(|(_, _, c)| !c.is_ascii_digit() && *c != '.')
+        .collect_vec();
+
+    let result = match *part {
+        Part::Part1 => numbers
+            .iter()
+            .filter(|n| symbols.iter().any(|(x, y, _)| around(n, *x, *y)))
+            .map(|(value, ..)| value)
+            .sum(),
+        Part::Part2 => symbols
+            .iter()
+            .filter(|(_, _, c)| *c == '*')
+            .map(|(x, y, _)| numbers.iter().filter(|n| around(n, *x, *y)).collect_vec())
+            .filter(|gears| gears.len() == 2)
+            .map(|gears| gears[0].0 * gears[1].0)
+            .sum(),
+    };
+
+    Ok(result)
+}
+
+/// Day 4 - Keep a running count of copies for every card.
+pub fn day_4(input: &str, part: &Part) -> Result<usize, Report> {
+    let matches = input
+        .split('\n')
+        .map(|line| {
+            let (_, numbers) = line.split_once(':').ok_or_else(|| eyre!("Malformed card"))?;
+            let (winning, have) = numbers.split_once('|').ok_or_else(|| eyre!("Malformed card"))?;
+            let winning = winning.split_whitespace().collect_vec();
+            Ok(have.split_whitespace().filter(|n| winning.contains(n)).count())
+        })
+        .collect::<Result<Vec<_>, Report>>()?;
+
+    let result = match *part {
+        Part::Part1 => matches.iter().filter(|m| **m > 0).map(|m| 1 << (m - 1)).sum(),
+        Part::Part2 => {
+            let mut copies = vec![1; matches.len()];
+            for (i, m) in matches.iter().enumerate() {
+                for j in i + 1..=i + m {
+                    if j < copies.len() {
+                        copies[j] += copies[i];
+                    }
+                }
+            }
+            copies.iter().sum()
+        }
+    };
+
+    Ok(result)
+}
+
+/// Day 5 - Push every single seed through the almanac.
+pub fn day_5(input: &str, part: &Part) -> Result<usize, Report> {
+    let sections = input.split("\n\n").collect_vec();
+    let seeds = sections[0].split(' ').filter_map(|s| s.parse::<usize>().ok()).collect_vec();
+
+    let maps = sections[1..]
+        .iter()
+        .map(|section| {
+            section
+                .split('\n')
+                .skip(1)
+                .map(|l| l.split(' ').filter_map(|n| n.parse::<usize>().ok()).collect_vec())
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let location = |seed: usize| {
+        maps.iter().fold(seed, |value, map| {
+            map.iter()
+                .find(|m| value >= m[1] && value < m[1] + m[2])
+                .map(|m| value - m[1] + m[0])
+                .unwrap_or(value)
+        })
+    };
+
+    let seeds = match *part {
+        Part::Part1 => seeds,
+        Part::Part2 => seeds.chunks(2).flat_map(|c| c[0]..c[0] + c[1]).collect_vec(),
+    };
+
+    seeds.into_iter().map(location).min().ok_or_else(|| eyre!("No seeds"))
+}
+
+/// Day 6 - Try every possible hold time.
+pub fn day_6(input: &str, part: &Part) -> Result<usize, Report> {
+    let lines = input
+        .split('\n')
+        .map(|l| {
+            let numbers = l.split_whitespace().skip(1);
+            match *part {
+                Part::Part1 => numbers.map(|n| n.to_string()).collect_vec(),
+                Part::Part2 => vec![numbers.collect::<String>()],
+            }
+        })
+        .collect_vec();
+
+    lines[0].iter().zip(&lines[1]).try_fold(1, |result, (time, distance)| {
+        let (time, distance): (usize, usize) = (time.parse()?, distance.parse()?);
+        let wins = (0..=time).filter(|hold| hold * (time - hold) > distance).count();
+        Ok(result * wins)
+    })
+}
+
+/// Day 7 - Try every card a joker could be.
+pub fn day_7(input: &str, part: &Part) -> Result<usize, Report> {
+    let labels = match *part {
+        Part::Part1 => "23456789TJQKA",
+        Part::Part2 => "J23456789TQKA",
+    };
+
+    // stronger hands have higher counts of the same card
+    let strength = |cards: &[char]| {
+        let mut counts = cards.iter().counts().into_values().collect_vec();
+        counts.sort();
+        counts.reverse();
+        counts
+    };
+
+    let mut hands = input
+        .split('\n')
+        .map(|line| {
+            let (cards, bid) = line.split_once(' ').ok_or_else(|| eyre!("Malformed hand"))?;
+            let cards = cards.chars().collect_vec();
+            let jokers =
+                cards.iter().positions(|c| *c == 'J' && *part == Part::Part2).collect_vec();
+
+            let best = (0..jokers.len())
+                .map(|_| labels[1..].chars())
+                .multi_cartesian_product()
+                .map(|replacement| {
+                    let mut cards = cards.clone();
+                    jokers.iter().zip(replacement).for_each(|(i, c)| cards[*i] = c);
+                    strength(&cards)
+                })
+                .max()
+                .unwrap_or_else(|| strength(&cards));
+
+            let ranks = cards.iter().map(|c| labels.find(*c)).collect_vec();
+            Ok((best, ranks, bid.parse::<usize>()?))
+        })
+        .collect::<Result<Vec<_>, Report>>()?;
+
+    hands.sort();
+    Ok(hands.iter().enumerate().map(|(i, (_, _, bid))| (i + 1) * bid).sum())
+}
+
+/// Day 8 - Walk every ghost one step at a time until they are all at a Z node.
+pub fn day_8(input: &str, part: &Part) -> Result<usize, Report> {
+    let (directions, nodes) = input.split_once("\n\n").ok_or_else(|| eyre!("Malformed map"))?;
+    let network: BTreeMap<&str, (&str, &str)> =
+        nodes.split('\n').map(|l| (&l[0..3], (&l[7..10], &l[12..15]))).collect();
+
+    let (mut ghosts, at_end): (Vec<&str>, fn(&str) -> bool) = match *part {
+        Part::Part1 => (vec!["AAA"], |n| n == "ZZZ"),
+        Part::Part2 => (
+            network.keys().filter(|n| n.ends_with('A')).copied().collect(),
+            |n| n.ends_with('Z'),
+        ),
+    };
+
+    let limit = 100_000_000;
+    for (steps, direction) in directions.chars().cycle().take(limit).enumerate() {
+        if ghosts.iter().all(|g| at_end(g)) {
+            return Ok(steps);
+        }
+        ghosts = ghosts
+            .iter()
+            .map(|g| match direction {
+                'L' => network[g].0,
+                _ => network[g].1,
+            })
+            .collect();
+    }
+
+    Err(eyre!("Ghosts did not arrive within {limit} steps."))
+}
+
+/// Day 9 - Take differences until they are all zero.
+pub fn day_9(input: &str, part: &Part) -> Result<usize, Report> {
+    let extrapolated = input
+        .split('\n')
+        .map(|l| {
+            let mut row =
+                l.split(' ').map(|n| n.parse::<isize>()).collect::<Result<Vec<_>, _>>()?;
+            let mut rows = Vec::new();
+            while row.iter().any(|n| *n != 0) {
+                let next = row.iter().tuple_windows().map(|(a, b)| b - a).collect_vec();
+                rows.push(row);
+                row = next;
+            }
+
+            let value = match *part {
+                Part::Part1 => rows.iter().rev().fold(0, |v, r| r[r.len() - 1] + v),
+                Part::Part2 => rows.iter().rev().fold(0, |v, r| r[0] - v),
+            };
+            Ok(value)
+        })
+        .collect::<Result<Vec<_>, Report>>()?;
+
+    // same risky conversion as the solution
+    Ok(extrapolated.iter().sum::<isize>() as usize)
+}
+
+/// Day 10 - Walk the loop, then draw every tile at 3x scale and flood the outside.
+pub fn day_10(input: &str, part: &Part) -> Result<usize, Report> {
+    let mut tiles = input.split('\n').map(|l| l.chars().collect_vec()).collect_vec();
+    let (height, width) = (tiles.len() as isize, tiles[0].len() as isize);
+    let tile = |tiles: &Vec<Vec<char>>, x: isize, y: isize| match x >= 0
+        && y >= 0
+        && x < width
+        && y < height
+    {
+        true => tiles[y as usize][x as usize],
+        false => '.',
+    };
+
+    // the (dx, dy) directions a pipe connects to
+    let connections = |c: char| match c {
+        '|' => vec![(0, -1), (0, 1)],
+        '-' => vec![(-1, 0), (1, 0)],
+        'L' => vec![(0, -1), (1, 0)],
+        'J' => vec![(0, -1), (-1, 0)],
+        '7' => vec![(0, 1), (-1, 0)],
+        'F' => vec![(0, 1), (1, 0)],
+        _ => vec![],
+    };
+
+    // replace the start with the pipe that connects to its neighbors
+    let y = tiles.iter().position(|r| r.contains(&'S')).ok_or_else(|| eyre!("No start"))?;
+    let x = tiles[y].iter().position(|c| *c == 'S').unwrap();
+    let (sx, sy) = (x as isize, y as isize);
+    let start = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+        .into_iter()
+        .filter(|(dx, dy)| connections(tile(&tiles, sx + dx, sy + dy)).contains(&(-dx, -dy)))
+        .collect_vec();
+    tiles[y][x] = "|-LJ7F"
+        .chars()
+        .find(|c| connections(*c).iter().all(|d| start.contains(d)) && start.len() == 2)
+        .ok_or_else(|| eyre!("Start does not connect to exactly two pipes"))?;
+
+    // walk the loop
+    let mut pipe_loop = vec![(sx, sy)];
+    let (mut x, mut y) = (sx, sy);
+    let (mut dx, mut dy) = connections(tiles[y as usize][x as usize])[0];
+    loop {
+        (x, y) = (x + dx, y + dy);
+        if (x, y) == (sx, sy) {
+            break;
+        }
+        pipe_loop.push((x, y));
+        (dx, dy) = connections(tile(&tiles, x, y))
+            .into_iter()
+            .find(|d| *d != (-dx, -dy))
+            .ok_or_else(|| eyre!("Loop is broken at ({x}, {y})"))?;
+    }
+
+    if *part == Part::Part1 {
+        return Ok(pipe_loop.len() / 2);
+    }
+
+    // draw the loop at 3x scale, each pipe is a center dot plus its connections
+    let (w3, h3) = (width as usize * 3, height as usize * 3);
+    let mut wall = vec![vec![false; w3]; h3];
+    pipe_loop.iter().for_each(|(x, y)| {
+        let (cx, cy) = (*x * 3 + 1, *y * 3 + 1);
+        wall[cy as usize][cx as usize] = true;
+        connections(tile(&tiles, *x, *y)).iter().for_each(|(dx, dy)| {
+            wall[(cy + dy) as usize][(cx + dx) as usize] = true;
+        });
+    });
+
+    // flood the outside, starting from the border
+    let mut outside = vec![vec![false; w3]; h3];
+    let mut queue = (0..w3)
+        .flat_map(|x| [(x, 0), (x, h3 - 1)])
+        .chain((0..h3).flat_map(|y| [(0, y), (w3 - 1, y)]))
+        .collect_vec();
+    while let Some((x, y)) = queue.pop() {
+        if outside[y][x] || wall[y][x] {
+            continue;
+        }
+        outside[y][x] = true;
+        if x > 0 {
+            queue.push((x - 1, y));
+        }
+        if y > 0 {
+            queue.push((x, y - 1));
+        }
+        if x + 1 < w3 {
+            queue.push((x + 1, y));
+        }
+        if y + 1 < h3 {
+            queue.push((x, y + 1));
+        }
+    }
+
+    let inside = (0..width)
+        .cartesian_product(0..height)
+        .filter(|(x, y)| !pipe_loop.contains(&(*x, *y)))
+        .filter(|(x, y)| !outside[(*y * 3 + 1) as usize][(*x * 3 + 1) as usize])
+        .count();
+
+    Ok(inside)
+}
+
+/// Day 11 - Add up the cost of every row and column crossed between each pair.
+pub fn day_11(input: &str, part: &Part) -> Result<usize, Report> {
+    let tiles = input.split('\n').map(|l| l.chars().collect_vec()).collect_vec();
+    let expansion = match *part {
+        Part::Part1 => 2,
+        Part::Part2 => 1_000_000,
+    };
+
+    let galaxies = tiles
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().positions(|c| *c == '#').map(move |x| (x, y)))
+        .collect_vec();
+
+    // empty rows and columns cost more to cross
+    let row_cost = |y: usize| match tiles[y].contains(&'#') {
+        true => 1,
+        false => expansion,
+    };
+    let column_cost = |x: usize| match tiles.iter().any(|row| row[x] == '#') {
+        true => 1,
+        false => expansion,
+    };
+
+    let result = galaxies
+        .iter()
+        .tuple_combinations()
+        .map(|((x1, y1), (x2, y2))| {
+            let columns: usize = (*x1.min(x2)..*x1.max(x2)).map(column_cost).sum();
+            let rows: usize = (*y1.min(y2)..*y1.max(y2)).map(row_cost).sum();
+            columns + rows
+        })
+        .sum();
+
+    Ok(result)
+}
+
+/// Day 12 - Count arrangements one spring at a time.
+///
+/// Part 1 tries every way of filling in the unknown springs. The unfolded
+/// records of part 2 have far too many arrangements to list, so instead
+/// count the ways to reach each (spring, group, run length) state.
+pub fn day_12(input: &str, part: &Part) -> Result<usize, Report> {
+    let unfold = match *part {
+        Part::Part1 => 1,
+        Part::Part2 => 5,
+    };
+
+    input
+        .split('\n')
+        .map(|line| {
+            let (springs, damage) =
+                line.split_once(' ').ok_or_else(|| eyre!("Malformed record"))?;
+            let springs = vec![springs; unfold].join("?").chars().collect_vec();
+            let damage =
+                damage.split(',').map(|d| d.parse::<usize>()).collect::<Result<Vec<_>, _>>()?;
+            let damage = damage.repeat(unfold);
+
+            let arrangements = match *part {
+                Part::Part1 => enumerate_springs(&mut springs.clone(), 0, &damage),
+                Part::Part2 => count_springs(&springs, &damage),
+            };
+            Ok(arrangements)
+        })
+        .sum()
+}
+
+/// Fill in every unknown spring, and check the groups of damaged springs.
+fn enumerate_springs(springs: &mut Vec<char>, i: usize, damage: &[usize]) -> usize {
+    if i == springs.len() {
+        let groups = springs.split(|c| *c == '.').map(|g| g.len()).filter(|g| *g > 0).collect_vec();
+        return (groups == damage) as usize;
+    }
+    if springs[i] != '?' {
+        return enumerate_springs(springs, i + 1, damage);
+    }
+
+    let mut result = 0;
+    for c in ['.', '#'] {
+        springs[i] = c;
+        result += enumerate_springs(springs, i + 1, damage);
+    }
+    springs[i] = '?';
+    result
+}
+
+/// Count arrangements by tracking how many ways lead to each state of
+/// (completed groups, length of the current damaged run).
+fn count_springs(springs: &[char], damage: &[usize]) -> usize {
+    let mut states: BTreeMap<(usize, usize), usize> = [((0, 0), 1)].into_iter().collect();
+
+    for c in springs {
+        let mut next: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for ((group, run), ways) in states {
+            // operational, closes the current run if it is the right length
+            if *c != '#' {
+                if run == 0 {
+                    *next.entry((group, 0)).or_default() += ways;
+                } else if damage[group] == run {
+                    *next.entry((group + 1, 0)).or_default() += ways;
+                }
+            }
+            // damaged, extends the current run if there's room
+            if *c != '.' && group < damage.len() && run < damage[group] {
+                *next.entry((group, run + 1)).or_default() += ways;
+            }
+        }
+        states = next;
+    }
+
+    states
+        .into_iter()
+        .filter(|((group, run), _)| match *run {
+            0 => *group == damage.len(),
+            _ => *group == damage.len() - 1 && *run == damage[*group],
+        })
+        .map(|(_, ways)| ways)
+        .sum()
+}
+
+#[test]
+fn agreement() -> Result<(), Report> {
+    for day in [
+        "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12",
+    ] {
+        if let Some(disagreement) = differential(day, 10, 10)? {
+            return Err(eyre!("{disagreement}"));
+        }
+    }
+    Ok(())
+}
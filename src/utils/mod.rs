@@ -1,7 +1,9 @@
 pub mod table;
 
-use color_eyre::eyre::{Report, Result};
+use color_eyre::eyre::{eyre, Report, Result};
+use std::cell::Cell;
 use std::path::PathBuf;
+use std::sync::Once;
 
 pub fn read_to_string(path: &str) -> Result<String, Report> {
     let path = PathBuf::from(path);
@@ -13,3 +15,53 @@ pub fn read_to_string(path: &str) -> Result<String, Report> {
 
     Ok(input)
 }
+
+thread_local! {
+    /// Whether this thread is inside [`catch_panic`], and should panic quietly.
+    static CATCHING: Cell<bool> = Cell::new(false);
+}
+
+/// Run a fallible function, converting a panic into an error.
+///
+/// The panic message isn't printed, since it becomes the error. Panics
+/// outside of this, or on other threads, still go to the previous hook.
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T, Report>) -> Result<T, Report> {
+    static QUIET_HOOK: Once = Once::new();
+    QUIET_HOOK.call_once(|| {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(|c| c.get()) {
+                hook(info);
+            }
+        }));
+    });
+
+    let catching = CATCHING.with(|c| c.replace(true));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+    CATCHING.with(|c| c.set(catching));
+
+    match result {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = match (
+                payload.downcast_ref::<&str>(),
+                payload.downcast_ref::<String>(),
+            ) {
+                (Some(message), _) => message.to_string(),
+                (_, Some(message)) => message.clone(),
+                _ => "unknown panic".to_string(),
+            };
+            Err(eyre!("Panicked: {message}"))
+        }
+    }
+}
+
+#[test]
+fn catch_panic_hook() {
+    let error = catch_panic::<()>(|| panic!("quiet")).unwrap_err();
+    assert_eq!(error.to_string(), "Panicked: quiet");
+    // the previous hook is still used outside of catch_panic
+    let outside = std::panic::catch_unwind(|| panic!("loud"));
+    assert!(outside.is_err());
+    assert!(!CATCHING.with(|c| c.get()));
+}
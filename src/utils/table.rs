@@ -1,12 +1,189 @@
+use clap::ValueEnum;
 use color_eyre::eyre::{eyre, Report, Result};
 use itertools::Itertools;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// ----------------------------------------------------------------------------
+// Output Format
+// ----------------------------------------------------------------------------
+
+/// Text format to render a table in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Markdown,
+    /// Box-drawing characters, for the terminal.
+    Box,
+    /// Plain ASCII grid, for the terminal.
+    Grid,
+    /// Aligned columns without borders, for the terminal.
+    Compact,
+    Csv,
+    Tsv,
+    Html,
+    Json,
+    Latex,
+}
+
+/// The format chosen on the command line, for tables printed deep inside solutions.
+static OUTPUT_FORMAT: RwLock<Format> = RwLock::new(Format::Markdown);
+
+/// Set the format that [`Table::render`] uses for printed tables.
+pub fn set_output_format(format: Format) {
+    *OUTPUT_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = format;
+}
+
+/// Get the format for printed tables, markdown unless set otherwise.
+pub fn output_format() -> Format {
+    *OUTPUT_FORMAT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// How printed tables fit the terminal, see [`Table::to_layout`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Layout {
+    /// Maximum line width, wider tables are split into chunks of columns.
+    pub width: Option<usize>,
+    /// Maximum display width of a cell, longer cells end with an ellipsis.
+    pub cell_width: Option<usize>,
+    /// Number of rows per page, the rows shown are summarized on the last line.
+    /// A limit of 0 shows every row.
+    pub rows: Option<usize>,
+    /// Page of rows to show, counting from 0, when `rows` is set.
+    pub page: usize,
+}
+
+impl Layout {
+    /// Fit the width of the terminal, or 120 columns if it's unknown.
+    pub fn terminal() -> Self {
+        let width = terminal_size::terminal_size()
+            .map(|(w, _)| w.0 as usize)
+            .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+            .unwrap_or(120);
+        Layout {
+            width: Some(width),
+            ..Default::default()
+        }
+    }
+}
+
+static OUTPUT_LAYOUT: RwLock<Layout> = RwLock::new(Layout {
+    width: None,
+    cell_width: None,
+    rows: None,
+    page: 0,
+});
+
+/// Set the layout that [`Table::render`] uses for printed tables.
+pub fn set_output_layout(layout: Layout) {
+    *OUTPUT_LAYOUT.write().unwrap_or_else(|e| e.into_inner()) = layout;
+}
+
+/// Get the layout for printed tables, unlimited unless set otherwise.
+pub fn output_layout() -> Layout {
+    *OUTPUT_LAYOUT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Shorten a cell to a display width, ending with an ellipsis if it was cut.
+pub fn truncate(cell: &str, width: usize) -> String {
+    if cell.width() <= width {
+        return cell.to_string();
+    }
+    let mut truncated = String::new();
+    for c in cell.chars() {
+        if truncated.width() + c.width().unwrap_or(0) + 1 > width {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push('…');
+    truncated
+}
+
+// ----------------------------------------------------------------------------
+// Alignment
+// ----------------------------------------------------------------------------
+
+/// Horizontal alignment of a column's cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Alignment {
+    /// Right aligned if every cell is a number, otherwise centered.
+    #[default]
+    Auto,
+    Left,
+    Right,
+    Center,
+}
+
+// ----------------------------------------------------------------------------
+// Queries
+// ----------------------------------------------------------------------------
+
+/// Type of a column, inferred from its cells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnType {
+    Int,
+    Float,
+    String,
+}
+
+/// Sort order of a column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+/// Summary of a column within a group of rows.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Mean,
+}
+
+/// Compare two cells as the column's type, empty cells (or ones that aren't
+/// numbers in a numeric column) come first.
+pub fn compare(a: &str, b: &str, kind: ColumnType) -> Ordering {
+    let (a, b) = (a.trim(), b.trim());
+    match kind {
+        ColumnType::Int => a.parse::<i64>().ok().cmp(&b.parse::<i64>().ok()),
+        ColumnType::Float => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.total_cmp(&b),
+            (a, b) => a.is_ok().cmp(&b.is_ok()),
+        },
+        ColumnType::String => a.cmp(b),
+    }
+}
+
+/// Which rows to keep when joining tables.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Join {
+    /// Rows with a key in both tables.
+    Inner,
+    /// Every row of the left table.
+    Left,
+    /// Every row of both tables.
+    Outer,
+}
+
+// ----------------------------------------------------------------------------
+// Table
+// ----------------------------------------------------------------------------
 
 #[derive(Debug, Clone)]
 pub struct Table {
     pub headers: Vec<String>,
     pub rows: Vec<Vec<String>>,
+    /// Alignment of each column, missing columns are [`Alignment::Auto`].
+    pub alignments: Vec<Alignment>,
     pub path: PathBuf,
 }
 
@@ -22,9 +199,151 @@ impl Table {
             path: PathBuf::new(),
             headers: Vec::new(),
             rows: Vec::new(),
+            alignments: Vec::new(),
         }
     }
 
+    /// Read a table from a CSV file, the first record is the headers.
+    pub fn from_csv(path: &Path) -> Result<Table, Report> {
+        let mut table = Table::parse_delimited(&Table::read(path)?, ',')?;
+        table.path = path.to_path_buf();
+        Ok(table)
+    }
+
+    /// Read a table from a TSV file, the first record is the headers.
+    pub fn from_tsv(path: &Path) -> Result<Table, Report> {
+        let mut table = Table::parse_delimited(&Table::read(path)?, '\t')?;
+        table.path = path.to_path_buf();
+        Ok(table)
+    }
+
+    /// Read a table from a markdown file, as written by [`Table::to_markdown`].
+    pub fn from_markdown(path: &Path) -> Result<Table, Report> {
+        let mut table = Table::parse_markdown(&Table::read(path)?)?;
+        table.path = path.to_path_buf();
+        Ok(table)
+    }
+
+    /// Read a table from a CSV, TSV or markdown file, by its extension.
+    pub fn from_path(path: &Path) -> Result<Table, Report> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Table::from_csv(path),
+            Some("tsv") => Table::from_tsv(path),
+            Some("md") => Table::from_markdown(path),
+            _ => Err(eyre!("Table {path:?} is not a .csv, .tsv or .md file.")),
+        }
+    }
+
+    fn read(path: &Path) -> Result<String, Report> {
+        std::fs::read_to_string(path).map_err(|e| eyre!("Failed to read table {path:?}: {e}"))
+    }
+
+    /// Parse delimited text, where fields can be wrapped in double quotes to
+    /// contain the delimiter, newlines, or doubled quotes (`""`).
+    pub fn parse_delimited(text: &str, delimiter: char) -> Result<Table, Report> {
+        let mut records: Vec<Vec<String>> = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match (quoted, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                (true, '"') => quoted = false,
+                (true, c) => field.push(c),
+                (false, '"') if field.is_empty() => quoted = true,
+                (false, c) if c == delimiter => record.push(std::mem::take(&mut field)),
+                (false, '\r') if chars.peek() == Some(&'\n') => continue,
+                (false, '\n') => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                (false, c) => field.push(c),
+            }
+        }
+        if quoted {
+            return Err(eyre!("Row {} has an unclosed quote.", records.len()));
+        }
+        // the last record may not end with a newline
+        if !field.is_empty() || !record.is_empty() {
+            record.push(field);
+            records.push(record);
+        }
+
+        Table::from_records(records)
+    }
+
+    /// Parse a markdown table, pipes inside cells are escaped as `\|`.
+    pub fn parse_markdown(text: &str) -> Result<Table, Report> {
+        let mut lines = text.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+
+        let parse_line = |line: &str| -> Vec<String> {
+            let line = line.strip_prefix('|').unwrap_or(line);
+            let line = line.strip_suffix('|').filter(|l| !l.ends_with('\\')).unwrap_or(line);
+            let mut cells = vec![String::new()];
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' if chars.peek() == Some(&'|') => {
+                        chars.next();
+                        cells.last_mut().unwrap().push('|');
+                    }
+                    '|' => cells.push(String::new()),
+                    c => cells.last_mut().unwrap().push(c),
+                }
+            }
+            cells.into_iter().map(|c| c.trim().to_string()).collect_vec()
+        };
+
+        let headers = lines.next().map(parse_line).ok_or_else(|| eyre!("Table is empty."))?;
+        let frame = lines.next().map(parse_line).unwrap_or_default();
+        let is_frame =
+            |cell: &String| cell.contains('-') && cell.chars().all(|c| c == '-' || c == ':');
+        if frame.len() != headers.len() || !frame.iter().all(is_frame) {
+            return Err(eyre!("Row 0 is not a header frame like |---|---|."));
+        }
+
+        let records = std::iter::once(headers).chain(lines.map(parse_line)).collect_vec();
+        let mut table = Table::from_records(records)?;
+        table.alignments = frame
+            .iter()
+            .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Alignment::Center,
+                (true, false) => Alignment::Left,
+                (false, true) => Alignment::Right,
+                (false, false) => Alignment::Auto,
+            })
+            .collect_vec();
+        Ok(table)
+    }
+
+    /// Build a table from records, the first is the headers.
+    ///
+    /// Rows are numbered from 1, after the headers.
+    fn from_records(records: Vec<Vec<String>>) -> Result<Table, Report> {
+        let mut records = records.into_iter();
+        let mut table = Table::new();
+        table.headers = records.next().ok_or_else(|| eyre!("Table is empty."))?;
+
+        for (i, row) in records.enumerate() {
+            if row.len() != table.headers.len() {
+                return Err(eyre!(
+                    "Row {} has {} columns, but there are {} headers.",
+                    i + 1,
+                    row.len(),
+                    table.headers.len()
+                ));
+            }
+            table.rows.push(row);
+        }
+
+        Ok(table)
+    }
+
     pub fn header_position(&self, header: &str) -> Result<usize, Report> {
         let pos =
             self.headers.iter().position(|h| h == header).ok_or_else(|| {
@@ -38,66 +357,918 @@ impl Table {
         let mut table = Table::new();
         let header_i = self.header_position(header)?;
         table.headers = self.headers.clone();
+        table.alignments = self.alignments.clone();
         table.rows = self.rows.iter().filter(|row| row[header_i] == pattern).cloned().collect_vec();
         Ok(table)
     }
 
-    /// Convert table to markdown format
+    /// Infer the type of a column from its non-empty cells.
+    pub fn column_type(&self, col_i: usize) -> ColumnType {
+        let mut cells = self.rows.iter().map(|row| row[col_i].trim()).filter(|c| !c.is_empty());
+        if cells.clone().next().is_none() {
+            ColumnType::String
+        } else if cells.clone().all(|c| c.parse::<i64>().is_ok()) {
+            ColumnType::Int
+        } else if cells.all(|c| c.parse::<f64>().is_ok()) {
+            ColumnType::Float
+        } else {
+            ColumnType::String
+        }
+    }
+
+    /// Keep the rows where a column's cell satisfies the predicate.
+    pub fn filter_by(
+        &self,
+        header: &str,
+        predicate: impl Fn(&str) -> bool,
+    ) -> Result<Table, Report> {
+        let header_i = self.header_position(header)?;
+        let mut table = self.clone();
+        table.rows.retain(|row| predicate(&row[header_i]));
+        Ok(table)
+    }
+
+    /// Keep the rows where a column's cell matches the regular expression.
+    pub fn filter_regex(&self, header: &str, pattern: &str) -> Result<Table, Report> {
+        let regex = Regex::new(pattern)?;
+        self.filter_by(header, |cell| regex.is_match(cell))
+    }
+
+    /// Keep the rows matching an expression like `time>10`, `variant!=default`
+    /// or `day~^1[0-2]$` (a regex). Comparisons use the column's type.
+    pub fn filter_expr(&self, expr: &str) -> Result<Table, Report> {
+        let operators = ["!=", "<=", ">=", "=", "<", ">", "~"];
+        let (i, op) = operators
+            .iter()
+            .filter_map(|op| expr.find(op).map(|i| (i, *op)))
+            .min_by_key(|(i, op)| (*i, std::cmp::Reverse(op.len())))
+            .ok_or_else(|| eyre!("Filter '{expr}' has no operator: {operators:?}"))?;
+        let (header, value) = (expr[..i].trim(), expr[i + op.len()..].trim());
+
+        if op == "~" {
+            return self.filter_regex(header, value);
+        }
+        let kind = self.column_type(self.header_position(header)?);
+        let parses = match kind {
+            ColumnType::Int => value.parse::<i64>().is_ok(),
+            ColumnType::Float => value.parse::<f64>().is_ok(),
+            ColumnType::String => true,
+        };
+        if !parses {
+            return Err(eyre!("'{value}' is not a {kind:?}"));
+        }
+        self.filter_by(header, |cell| {
+            let ordering = compare(cell, value, kind);
+            match op {
+                "=" => ordering.is_eq(),
+                "!=" => ordering.is_ne(),
+                "<" => ordering.is_lt(),
+                "<=" => ordering.is_le(),
+                ">" => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }
+        })
+    }
+
+    /// Sort the rows by several columns, the first key takes precedence.
+    pub fn sort(&self, keys: &[(&str, Order)]) -> Result<Table, Report> {
+        let keys = keys
+            .iter()
+            .map(|(header, order)| {
+                let header_i = self.header_position(header)?;
+                Ok((header_i, self.column_type(header_i), *order))
+            })
+            .collect::<Result<Vec<_>, Report>>()?;
+
+        let mut table = self.clone();
+        table.rows.sort_by(|a, b| {
+            let orderings = keys.iter().map(|(i, kind, order)| match order {
+                Order::Ascending => compare(&a[*i], &b[*i], *kind),
+                Order::Descending => compare(&b[*i], &a[*i], *kind),
+            });
+            orderings.fold(Ordering::Equal, Ordering::then)
+        });
+        Ok(table)
+    }
+
+    /// Keep only some columns, in the given order.
+    pub fn select(&self, headers: &[&str]) -> Result<Table, Report> {
+        let positions =
+            headers.iter().map(|h| self.header_position(h)).collect::<Result<Vec<_>, _>>()?;
+        Ok(self.select_positions(&positions))
+    }
+
+    fn select_positions(&self, positions: &[usize]) -> Table {
+        let mut table = Table::new();
+        table.path = self.path.clone();
+        table.headers = positions.iter().map(|i| self.headers[*i].clone()).collect_vec();
+        table.alignments = positions
+            .iter()
+            .map(|i| self.alignments.get(*i).copied().unwrap_or_default())
+            .collect_vec();
+        table.rows = self
+            .rows
+            .iter()
+            .map(|row| positions.iter().map(|i| row[*i].clone()).collect_vec())
+            .collect_vec();
+        table
+    }
+
+    /// Group rows by key columns, with one aggregate column per `(header, aggregate)`.
     ///
-    /// TBD: error handling for empty rows!
-    pub fn to_markdown(&self) -> Result<String, Report> {
-        // get the maximum width of each column
-        let col_widths = self
-            // iterate through columns/headers
-            .headers
+    /// Groups are in order of first appearance, and aggregate columns are named
+    /// like `sum(time)`, or just `count`.
+    pub fn group_by(
+        &self,
+        keys: &[&str],
+        aggregates: &[(&str, Aggregate)],
+    ) -> Result<Table, Report> {
+        let keys = keys.iter().map(|h| self.header_position(h)).collect::<Result<Vec<_>, _>>()?;
+        let aggregates = aggregates
             .iter()
-            .enumerate()
-            .map(|(col_i, header)| {
-                self
-                    // iterate through this column's rows,
-                    // get max string width, +2 to add space on either side
-                    .rows
-                    .iter()
-                    .map(|row| {
-                        let cell_width = (*row[col_i]).len();
-                        if cell_width >= header.len() {
-                            cell_width + 2
-                        } else {
-                            header.len() + 2
+            .map(|(header, aggregate)| Ok((self.header_position(header)?, *aggregate)))
+            .collect::<Result<Vec<_>, Report>>()?;
+
+        let groups = self
+            .rows
+            .iter()
+            .into_group_map_by(|row| keys.iter().map(|i| row[*i].clone()).collect_vec());
+        let order =
+            self.rows.iter().map(|row| keys.iter().map(|i| row[*i].clone()).collect_vec()).unique();
+
+        let mut table = Table::new();
+        table.path = self.path.clone();
+        table.headers = keys.iter().map(|i| self.headers[*i].clone()).collect_vec();
+        table.headers.extend(aggregates.iter().map(|(i, aggregate)| match aggregate {
+            Aggregate::Count => "count".to_string(),
+            _ => format!(
+                "{}({})",
+                format!("{aggregate:?}").to_lowercase(),
+                self.headers[*i]
+            ),
+        }));
+
+        for key in order {
+            let rows = &groups[&key];
+            let mut row = key.clone();
+            for (i, aggregate) in &aggregates {
+                let kind = self.column_type(*i);
+                let cells =
+                    rows.iter().map(|row| row[*i].as_str()).filter(|c| !c.trim().is_empty());
+                let numbers = || {
+                    cells
+                        .clone()
+                        .map(|c| {
+                            c.trim().parse::<f64>().map_err(|_| {
+                                eyre!("Can't aggregate '{c}' in column '{}'.", self.headers[*i])
+                            })
+                        })
+                        .collect::<Result<Vec<_>, Report>>()
+                };
+                let cell = match aggregate {
+                    Aggregate::Count => rows.len().to_string(),
+                    Aggregate::Min => {
+                        cells.min_by(|a, b| compare(a, b, kind)).unwrap_or_default().to_string()
+                    }
+                    Aggregate::Max => {
+                        cells.max_by(|a, b| compare(a, b, kind)).unwrap_or_default().to_string()
+                    }
+                    Aggregate::Sum if kind == ColumnType::Int => {
+                        cells.map(|c| c.trim().parse::<i64>()).sum::<Result<i64, _>>()?.to_string()
+                    }
+                    Aggregate::Sum => numbers()?.iter().sum::<f64>().to_string(),
+                    Aggregate::Mean => {
+                        let numbers = numbers()?;
+                        match numbers.is_empty() {
+                            true => String::new(),
+                            false => {
+                                (numbers.iter().sum::<f64>() / numbers.len() as f64).to_string()
+                            }
                         }
-                    })
-                    .max()
-                    .unwrap_or(header.len() + 2)
+                    }
+                };
+                row.push(cell);
+            }
+            table.rows.push(row);
+        }
+
+        Ok(table)
+    }
+
+    /// Swap rows and columns, the first column becomes the headers.
+    pub fn transpose(&self) -> Table {
+        let mut table = Table::new();
+        table.path = self.path.clone();
+        if self.headers.is_empty() {
+            return table;
+        }
+        table.headers = std::iter::once(self.headers[0].clone())
+            .chain(self.rows.iter().map(|row| row[0].clone()))
+            .collect_vec();
+        table.rows = (1..self.headers.len())
+            .map(|col_i| {
+                std::iter::once(self.headers[col_i].clone())
+                    .chain(self.rows.iter().map(|row| row[col_i].clone()))
+                    .collect_vec()
             })
             .collect_vec();
+        table
+    }
 
-        let mut markdown = String::from("|");
-        // frame in between headers and rows
-        let mut header_frame = String::from("|");
-
-        // Create the header line
-        for it in self.headers.iter().zip(col_widths.iter()) {
-            let (header, col_width) = it;
-            let cell = format!("{:^width$}|", header, width = col_width);
-            markdown.push_str(&cell);
+    /// Join with another table on key columns.
+    ///
+    /// The joined headers are the keys, then the other columns of this table,
+    /// then the other columns of the other table (suffixed with `_right` if the
+    /// name is taken). Unmatched rows are filled with empty cells.
+    pub fn join(&self, other: &Table, keys: &[&str], kind: Join) -> Result<Table, Report> {
+        let left_keys =
+            keys.iter().map(|k| self.header_position(k)).collect::<Result<Vec<_>, _>>()?;
+        let right_keys =
+            keys.iter().map(|k| other.header_position(k)).collect::<Result<Vec<_>, _>>()?;
+        let left_rest = (0..self.headers.len()).filter(|i| !left_keys.contains(i)).collect_vec();
+        let right_rest = (0..other.headers.len()).filter(|i| !right_keys.contains(i)).collect_vec();
 
-            let frame = format!("{}|", "-".repeat(*col_width));
-            header_frame.push_str(&frame);
+        let mut table = Table::new();
+        table.headers = keys.iter().map(|k| k.to_string()).collect_vec();
+        table.headers.extend(left_rest.iter().map(|i| self.headers[*i].clone()));
+        for i in &right_rest {
+            let header = &other.headers[*i];
+            match table.headers.contains(header) {
+                true => table.headers.push(format!("{header}_right")),
+                false => table.headers.push(header.clone()),
+            }
         }
-        markdown.push('\n');
-        markdown.push_str(&header_frame);
-        markdown.push('\n');
 
-        // Create the row lines
+        let cells = |row: &Vec<String>, positions: &[usize]| {
+            positions.iter().map(|i| row[*i].clone()).collect_vec()
+        };
+        let right = other.rows.iter().into_group_map_by(|row| cells(row, &right_keys));
+        let empty = |n: usize| vec![String::new(); n];
+
         for row in &self.rows {
-            markdown.push('|');
-            for (col_i, col_width) in col_widths.iter().enumerate() {
-                let cell = format!("{:^width$}|", row[col_i], width = col_width);
-                markdown.push_str(&cell);
+            let k = cells(row, &left_keys);
+            match right.get(&k) {
+                Some(matches) => {
+                    for other_row in matches {
+                        table.rows.push(
+                            [
+                                k.clone(),
+                                cells(row, &left_rest),
+                                cells(other_row, &right_rest),
+                            ]
+                            .concat(),
+                        );
+                    }
+                }
+                None if kind == Join::Inner => continue,
+                None => {
+                    table.rows.push([k, cells(row, &left_rest), empty(right_rest.len())].concat())
+                }
+            }
+        }
+
+        if kind == Join::Outer {
+            let left = self.rows.iter().map(|row| cells(row, &left_keys)).collect::<HashSet<_>>();
+            for other_row in
+                other.rows.iter().filter(|row| !left.contains(&cells(row, &right_keys)))
+            {
+                table.rows.push(
+                    [
+                        cells(other_row, &right_keys),
+                        empty(left_rest.len()),
+                        cells(other_row, &right_rest),
+                    ]
+                    .concat(),
+                );
+            }
+        }
+
+        Ok(table)
+    }
+
+    /// Append the rows of another table, matching columns by header.
+    ///
+    /// Headers only in the other table are added at the end, and cells of
+    /// missing columns are left empty.
+    pub fn concat(&self, other: &Table) -> Table {
+        let mut table = self.clone();
+        for header in &other.headers {
+            if !table.headers.contains(header) {
+                table.headers.push(header.clone());
+                table.rows.iter_mut().for_each(|row| row.push(String::new()));
+            }
+        }
+
+        for row in &other.rows {
+            let cells =
+                table.headers.iter().map(|h| match other.headers.iter().position(|o| o == h) {
+                    Some(i) => row[i].clone(),
+                    None => String::new(),
+                });
+            table.rows.push(cells.collect_vec());
+        }
+        table
+    }
+
+    /// Compare with a newer version of the table, matching rows by key columns.
+    ///
+    /// Returns a table of the rows that were `added`, `removed` or `changed`,
+    /// with changed cells shown as `old -> new`. Columns are reconciled like
+    /// [`Table::concat`]. Keys must be unique within each table.
+    pub fn diff(&self, other: &Table, keys: &[&str]) -> Result<Table, Report> {
+        let old = self.concat(&Table {
+            rows: Vec::new(),
+            ..other.clone()
+        });
+        let new = Table {
+            rows: Vec::new(),
+            ..old.clone()
+        }
+        .concat(other);
+        let positions =
+            keys.iter().map(|k| old.header_position(k)).collect::<Result<Vec<_>, _>>()?;
+        let key = |row: &Vec<String>| positions.iter().map(|i| row[*i].clone()).collect_vec();
+
+        // position of each row by its key
+        let index = |table: &Table, name: &str| {
+            let mut rows = HashMap::new();
+            for (i, row) in table.rows.iter().enumerate() {
+                if rows.insert(key(row), i).is_some() {
+                    let k = key(row);
+                    return Err(eyre!("Key {k:?} is in the {name} table more than once."));
+                }
+            }
+            Ok(rows)
+        };
+        let old_rows = index(&old, "old")?;
+        let new_rows = index(&new, "new")?;
+
+        let mut table = Table::new();
+        table.headers =
+            std::iter::once("change".to_string()).chain(old.headers.clone()).collect_vec();
+
+        for row in &old.rows {
+            match new_rows.get(&key(row)).map(|i| &new.rows[*i]) {
+                None => table.rows.push([vec!["removed".to_string()], row.clone()].concat()),
+                Some(new_row) if new_row != row => {
+                    let cells = row.iter().zip(new_row.iter()).map(|(o, n)| match o == n {
+                        true => o.clone(),
+                        false => format!("{o} -> {n}"),
+                    });
+                    table
+                        .rows
+                        .push(std::iter::once("changed".to_string()).chain(cells).collect_vec());
+                }
+                Some(_) => continue,
+            }
+        }
+        for row in new.rows.iter().filter(|row| !old_rows.contains_key(&key(row))) {
+            table.rows.push([vec!["added".to_string()], row.clone()].concat());
+        }
+
+        Ok(table)
+    }
+
+    /// Render the table in the output format chosen on the command line.
+    pub fn render(&self) -> Result<String, Report> {
+        self.to_layout(output_format(), &output_layout())
+    }
+
+    /// Render the table within a layout.
+    ///
+    /// Cells are truncated, and only one page of rows is shown. The text
+    /// formats end with a line saying which rows those were, the others keep
+    /// their syntax intact and log it instead. If the table is still too wide,
+    /// the columns are split into chunks that each repeat the first (key)
+    /// column. Widths only apply to the text formats.
+    pub fn to_layout(&self, format: Format, layout: &Layout) -> Result<String, Report> {
+        let mut table = self.clone();
+        // keep the alignment of the whole column, before cells are cut
+        table.alignments = (0..self.headers.len()).map(|col_i| self.alignment(col_i)).collect_vec();
+
+        let text = matches!(
+            format,
+            Format::Markdown | Format::Box | Format::Grid | Format::Compact
+        );
+
+        let mut footer = String::new();
+        let limit = layout.rows.filter(|limit| *limit > 0 && table.rows.len() > *limit);
+        if let Some(limit) = limit {
+            let pages = table.rows.len().div_ceil(limit);
+            if layout.page >= pages {
+                return Err(eyre!(
+                    "Page {} is past the last page, {pages}.",
+                    layout.page + 1
+                ));
+            }
+            let start = layout.page * limit;
+            let end = (start + limit).min(table.rows.len());
+            let summary = format!("… rows {}-{end} of {}", start + 1, table.rows.len());
+            match text {
+                true => footer = format!("{summary}\n"),
+                false => log::warn!("Showing {}", &summary["… ".len()..]),
+            }
+            table.rows = table.rows[start..end].to_vec();
+        }
+
+        if !text || table.headers.is_empty() {
+            return Ok(table.to_format(format)? + &footer);
+        }
+
+        let mut cell_widths = vec![layout.cell_width.unwrap_or(usize::MAX); table.headers.len()];
+        // width of a column, with padding and one border
+        let column_width = |table: &Table, col_i: usize| {
+            let cells = table.rows.iter().map(|row| &row[col_i]).chain([&table.headers[col_i]]);
+            cells.map(|cell| cell.width()).max().unwrap_or(0) + 3
+        };
+        if let Some(width) = layout.width {
+            // a column can't be wider than the space left beside the key column
+            let key = column_width(&table, 0).min(width / 2);
+            cell_widths[0] = cell_widths[0].min(key.saturating_sub(3).max(1));
+            for w in cell_widths.iter_mut().skip(1) {
+                *w = (*w).min(width.saturating_sub(key + 4).max(1));
+            }
+        }
+        for row in std::iter::once(&mut table.headers).chain(table.rows.iter_mut()) {
+            for (cell, w) in row.iter_mut().zip(&cell_widths) {
+                *cell = truncate(cell, *w);
+            }
+        }
+
+        let Some(width) = layout.width else {
+            return Ok(table.to_format(format)? + &footer);
+        };
+
+        // greedily pack the other columns into chunks beside the key column
+        let key = column_width(&table, 0) + 1;
+        let mut chunks: Vec<Vec<usize>> = vec![vec![0]];
+        let mut used = key;
+        for col_i in 1..table.headers.len() {
+            let w = column_width(&table, col_i);
+            if used + w > width && chunks.last().is_some_and(|c| c.len() > 1) {
+                chunks.push(vec![0]);
+                used = key;
+            }
+            chunks.last_mut().unwrap().push(col_i);
+            used += w;
+        }
+
+        let chunks = chunks.iter().map(|c| table.select_positions(c).to_format(format));
+        Ok(chunks.collect::<Result<Vec<_>, _>>()?.join("\n") + &footer)
+    }
+
+    /// Render the table in a specific format.
+    pub fn to_format(&self, format: Format) -> Result<String, Report> {
+        match format {
+            Format::Markdown | Format::Box | Format::Grid | Format::Compact => self.to_text(format),
+            Format::Csv => self.to_delimited(','),
+            Format::Tsv => self.to_delimited('\t'),
+            Format::Html => self.to_html(),
+            Format::Json => self.to_json(),
+            Format::Latex => self.to_latex(),
+        }
+    }
+
+    /// Convert table to delimited text, quoting fields that contain the
+    /// delimiter, quotes or newlines. Read back with [`Table::parse_delimited`].
+    pub fn to_delimited(&self, delimiter: char) -> Result<String, Report> {
+        let quote = |cell: &String| match cell.contains([delimiter, '"', '\n', '\r']) {
+            true => format!("\"{}\"", cell.replace('"', "\"\"")),
+            false => cell.clone(),
+        };
+        let lines = std::iter::once(&self.headers)
+            .chain(&self.rows)
+            .map(|row| row.iter().map(quote).join(&delimiter.to_string()));
+        Ok(lines.map(|l| l + "\n").collect())
+    }
+
+    /// Convert table to an HTML table, with a header row.
+    pub fn to_html(&self) -> Result<String, Report> {
+        let escape = |cell: &String| {
+            cell.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+        let row = |cells: &Vec<String>, tag: &str| {
+            let cells = cells.iter().map(|c| format!("<{tag}>{}</{tag}>", escape(c))).join("");
+            format!("    <tr>{cells}</tr>\n")
+        };
+
+        let mut html = String::from("<table>\n  <thead>\n");
+        html.push_str(&row(&self.headers, "th"));
+        html.push_str("  </thead>\n  <tbody>\n");
+        self.rows.iter().for_each(|r| html.push_str(&row(r, "td")));
+        html.push_str("  </tbody>\n</table>\n");
+        Ok(html)
+    }
+
+    /// Convert table to a JSON array of objects, keyed by the headers.
+    pub fn to_json(&self) -> Result<String, Report> {
+        let escape = |cell: &String| {
+            let mut escaped = String::from("\"");
+            for c in cell.chars() {
+                match c {
+                    '"' => escaped.push_str("\\\""),
+                    '\\' => escaped.push_str("\\\\"),
+                    '\n' => escaped.push_str("\\n"),
+                    '\r' => escaped.push_str("\\r"),
+                    '\t' => escaped.push_str("\\t"),
+                    c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => escaped.push(c),
+                }
             }
-            markdown.push('\n');
+            escaped.push('"');
+            escaped
+        };
+
+        let objects = self.rows.iter().map(|row| {
+            let fields = self.headers.iter().zip(row);
+            let fields = fields.map(|(h, c)| format!("{}: {}", escape(h), escape(c))).join(", ");
+            format!("  {{{fields}}}")
+        });
+        let objects = objects.collect_vec().join(",\n");
+        match objects.is_empty() {
+            true => Ok("[]\n".to_string()),
+            false => Ok(format!("[\n{objects}\n]\n")),
         }
+    }
+
+    /// Convert table to a LaTeX tabular, with a rule under the headers.
+    pub fn to_latex(&self) -> Result<String, Report> {
+        let escape = |cell: &String| {
+            cell.chars()
+                .map(|c| match c {
+                    '\\' => "\\textbackslash{}".to_string(),
+                    '~' => "\\textasciitilde{}".to_string(),
+                    '^' => "\\textasciicircum{}".to_string(),
+                    '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{c}"),
+                    c => c.to_string(),
+                })
+                .collect::<String>()
+        };
+        let row =
+            |cells: &Vec<String>| format!("  {} \\\\\n", cells.iter().map(escape).join(" & "));
+
+        let columns = (0..self.headers.len())
+            .map(|col_i| match self.alignment(col_i) {
+                Alignment::Right => 'r',
+                Alignment::Center | Alignment::Auto => 'c',
+                Alignment::Left => 'l',
+            })
+            .collect::<String>();
+        let mut latex = format!("\\begin{{tabular}}{{{columns}}}\n");
+        latex.push_str("  \\hline\n");
+        latex.push_str(&row(&self.headers));
+        latex.push_str("  \\hline\n");
+        self.rows.iter().for_each(|r| latex.push_str(&row(r)));
+        latex.push_str("  \\hline\n\\end{tabular}\n");
+        Ok(latex)
+    }
+
+    /// Convert table to markdown format, with alignment markers in the header frame.
+    pub fn to_markdown(&self) -> Result<String, Report> {
+        self.to_text(Format::Markdown)
+    }
+
+    /// Render the table as aligned text, in one of the terminal styles.
+    fn to_text(&self, format: Format) -> Result<String, Report> {
+        // escape pipes, so they aren't read as column separators
+        let escape = |cell: &String| match format {
+            Format::Markdown => cell.replace('|', "\\|"),
+            _ => cell.clone(),
+        };
+        let headers = self.headers.iter().map(escape).collect_vec();
+        let rows = self.rows.iter().map(|row| row.iter().map(escape).collect_vec()).collect_vec();
+        if let Some(i) = rows.iter().position(|row| row.len() != headers.len()) {
+            return Err(eyre!(
+                "Row {} has {} columns, but there are {} headers.",
+                i + 1,
+                rows[i].len(),
+                headers.len()
+            ));
+        }
+
+        // get the maximum display width of each column
+        let widths = (0..headers.len())
+            .map(|col_i| {
+                let cells = rows.iter().map(|row| &row[col_i]).chain([&headers[col_i]]);
+                cells.map(|cell| cell.width()).max().unwrap_or(0)
+            })
+            .collect_vec();
+        let alignments = (0..headers.len()).map(|col_i| self.alignment(col_i)).collect_vec();
+
+        // pad a cell to the column width, with a space on either side
+        let pad = |cell: &String, col_i: usize| {
+            let space = widths[col_i] - cell.width();
+            let left = match alignments[col_i] {
+                Alignment::Left => 0,
+                Alignment::Right => space,
+                Alignment::Center | Alignment::Auto => space / 2,
+            };
+            format!(" {}{cell}{} ", " ".repeat(left), " ".repeat(space - left))
+        };
+
+        // horizontal rule with the border characters: left, line, middle, right
+        let rule = |[l, line, m, r]: [&str; 4]| {
+            let cells = widths.iter().map(|w| line.repeat(w + 2)).join(m);
+            format!("{l}{cells}{r}\n")
+        };
+        let line = |cells: &Vec<String>, [l, m, r]: [&str; 3]| {
+            let cells = cells.iter().enumerate().map(|(col_i, c)| pad(c, col_i)).join(m);
+            format!("{l}{cells}{r}\n")
+        };
+
+        let (top, separator, bottom, borders) = match format {
+            Format::Box => (
+                Some(rule(["┌", "─", "┬", "┐"])),
+                rule(["├", "─", "┼", "┤"]),
+                Some(rule(["└", "─", "┴", "┘"])),
+                ["│", "│", "│"],
+            ),
+            Format::Grid => {
+                let rule = rule(["+", "-", "+", "+"]);
+                (
+                    Some(rule.clone()),
+                    rule.clone(),
+                    Some(rule),
+                    ["|", "|", "|"],
+                )
+            }
+            Format::Compact => (None, rule(["", "-", "", ""]), None, ["", "", ""]),
+            _ => {
+                // markdown alignment markers
+                let frame = widths.iter().zip(&alignments).map(|(w, a)| {
+                    let dashes = "-".repeat(*w);
+                    match a {
+                        Alignment::Left => format!(":{dashes}-"),
+                        Alignment::Right => format!("-{dashes}:"),
+                        Alignment::Center | Alignment::Auto => format!(":{dashes}:"),
+                    }
+                });
+                (
+                    None,
+                    format!("|{}|\n", frame.collect_vec().join("|")),
+                    None,
+                    ["|", "|", "|"],
+                )
+            }
+        };
+
+        let mut text = top.unwrap_or_default();
+        text.push_str(&line(&headers, borders));
+        text.push_str(&separator);
+        rows.iter().for_each(|row| text.push_str(&line(row, borders)));
+        text.push_str(&bottom.unwrap_or_default());
+
+        Ok(text)
+    }
 
-        Ok(markdown)
+    /// Get the alignment of a column, automatic columns are right aligned if
+    /// they're numeric, and otherwise centered.
+    pub fn alignment(&self, col_i: usize) -> Alignment {
+        match self.alignments.get(col_i).copied().unwrap_or_default() {
+            Alignment::Auto => match self.column_type(col_i) {
+                ColumnType::Int | ColumnType::Float => Alignment::Right,
+                ColumnType::String => Alignment::Center,
+            },
+            alignment => alignment,
+        }
     }
 }
+
+#[test]
+fn round_trip() -> Result<(), Report> {
+    let mut table = Table::new();
+    table.headers = ["day", "answer", "notes"].map(String::from).to_vec();
+    table.rows = vec![
+        ["1", "142", "plain"].map(String::from).to_vec(),
+        ["2", "8", "comma, \"quote\" | pipe"].map(String::from).to_vec(),
+        ["3", "4361", "two\nlines"].map(String::from).to_vec(),
+    ];
+
+    let csv = "day,answer,notes\n1,142,plain\n2,8,\"comma, \"\"quote\"\" | pipe\"\n3,4361,\"two\nlines\"\n";
+    let observed = Table::parse_delimited(csv, ',')?;
+    assert_eq!(observed.headers, table.headers);
+    assert_eq!(observed.rows, table.rows);
+
+    // markdown cells can't have newlines
+    table.rows.pop();
+    let observed = Table::parse_markdown(&table.to_markdown()?)?;
+    assert_eq!(observed.headers, table.headers);
+    assert_eq!(observed.rows, table.rows);
+
+    let error = Table::parse_delimited("a\tb\n1\t2\n3", '\t').unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Row 2 has 1 columns, but there are 2 headers."
+    );
+    Ok(())
+}
+
+#[test]
+fn formats() -> Result<(), Report> {
+    let mut table = Table::new();
+    table.headers = ["day", "notes"].map(String::from).to_vec();
+    table.rows = vec![["1", "a, \"b\" & <c>_"].map(String::from).to_vec()];
+
+    let observed = Table::parse_delimited(&table.to_format(Format::Csv)?, ',')?;
+    assert_eq!(observed.rows, table.rows);
+    let observed = Table::parse_delimited(&table.to_format(Format::Tsv)?, '\t')?;
+    assert_eq!(observed.rows, table.rows);
+
+    let html = table.to_format(Format::Html)?;
+    assert!(html.contains("<td>a, &quot;b&quot; &amp; &lt;c&gt;_</td>"));
+    let json = table.to_format(Format::Json)?;
+    assert_eq!(
+        json,
+        "[\n  {\"day\": \"1\", \"notes\": \"a, \\\"b\\\" & <c>_\"}\n]\n"
+    );
+    let latex = table.to_format(Format::Latex)?;
+    assert!(latex.contains("  1 & a, \"b\" \\& <c>\\_ \\\\\n"));
+    Ok(())
+}
+
+#[test]
+fn alignment() -> Result<(), Report> {
+    let mut table = Table::new();
+    table.headers = ["name", "count", "note"].map(String::from).to_vec();
+    table.rows = vec![
+        ["α", "10", "é"].map(String::from).to_vec(),
+        ["beta", "2", "日本"].map(String::from).to_vec(),
+    ];
+    table.alignments = vec![Alignment::Left];
+
+    let markdown = table.to_markdown()?;
+    let expected = "\
+| name | count | note |
+|:-----|------:|:----:|
+| α    |    10 |  é   |
+| beta |     2 | 日本 |
+";
+    assert_eq!(markdown, expected);
+    let observed = Table::parse_markdown(&markdown)?;
+    assert_eq!(
+        observed.alignments,
+        [Alignment::Left, Alignment::Right, Alignment::Center]
+    );
+
+    let boxed = table.to_format(Format::Box)?;
+    let mut lines = boxed.lines();
+    assert_eq!(lines.next(), Some("┌──────┬───────┬──────┐"));
+    assert_eq!(lines.next(), Some("│ name │ count │ note │"));
+    assert!(lines.next().is_some_and(|l| l.starts_with('├')));
+    Ok(())
+}
+
+#[test]
+fn queries() -> Result<(), Report> {
+    let mut table = Table::new();
+    table.headers = ["day", "variant", "time"].map(String::from).to_vec();
+    table.rows = vec![
+        ["10", "default", "2.5"].map(String::from).to_vec(),
+        ["9", "default", "10"].map(String::from).to_vec(),
+        ["10", "reference", "0.5"].map(String::from).to_vec(),
+        ["9", "reference", "1"].map(String::from).to_vec(),
+    ];
+    assert_eq!(table.column_type(0), ColumnType::Int);
+    assert_eq!(table.column_type(2), ColumnType::Float);
+    assert_eq!(table.column_type(1), ColumnType::String);
+
+    // numbers compare as numbers, not strings
+    let observed = table.filter_expr("time>=2")?;
+    assert_eq!(
+        observed.rows.iter().map(|r| &r[2]).collect_vec(),
+        ["2.5", "10"]
+    );
+    let observed = table.filter_expr("variant~^ref")?;
+    assert_eq!(observed.rows.len(), 2);
+    let error = table.filter_expr("time>abc").unwrap_err();
+    assert_eq!(error.to_string(), "'abc' is not a Float");
+
+    let observed = table.sort(&[("day", Order::Ascending), ("time", Order::Descending)])?;
+    assert_eq!(
+        observed.rows.iter().map(|r| &r[2]).collect_vec(),
+        ["10", "1", "2.5", "0.5"]
+    );
+
+    let observed = table.group_by(
+        &["variant"],
+        &[
+            ("day", Aggregate::Count),
+            ("time", Aggregate::Mean),
+            ("day", Aggregate::Sum),
+        ],
+    )?;
+    assert_eq!(
+        observed.headers,
+        ["variant", "count", "mean(time)", "sum(day)"]
+    );
+    assert_eq!(observed.rows[0], ["default", "2", "6.25", "19"]);
+
+    let observed = table.select(&["time", "day"])?.transpose();
+    assert_eq!(observed.headers, ["time", "2.5", "10", "0.5", "1"]);
+    assert_eq!(observed.rows, [["day", "10", "9", "10", "9"]]);
+    Ok(())
+}
+
+#[test]
+fn joins() -> Result<(), Report> {
+    let mut times = Table::new();
+    times.headers = ["day", "part", "time"].map(String::from).to_vec();
+    times.rows = vec![
+        ["1", "1", "5"].map(String::from).to_vec(),
+        ["1", "2", "7"].map(String::from).to_vec(),
+        ["2", "1", "3"].map(String::from).to_vec(),
+    ];
+    let mut baseline = Table::new();
+    baseline.headers = ["day", "part", "time", "answer"].map(String::from).to_vec();
+    baseline.rows = vec![
+        ["1", "1", "6", "142"].map(String::from).to_vec(),
+        ["3", "1", "9", "4361"].map(String::from).to_vec(),
+    ];
+
+    let observed = times.join(&baseline, &["day", "part"], Join::Inner)?;
+    assert_eq!(
+        observed.headers,
+        ["day", "part", "time", "time_right", "answer"]
+    );
+    assert_eq!(observed.rows, [["1", "1", "5", "6", "142"]]);
+    assert_eq!(
+        times.join(&baseline, &["day", "part"], Join::Left)?.rows.len(),
+        3
+    );
+    let observed = times.join(&baseline, &["day", "part"], Join::Outer)?;
+    assert_eq!(observed.rows[3], ["3", "1", "", "9", "4361"]);
+
+    let observed = times.concat(&baseline);
+    assert_eq!(observed.headers, baseline.headers);
+    assert_eq!(observed.rows[0], ["1", "1", "5", ""]);
+
+    let observed = times.diff(&baseline, &["day", "part"])?;
+    assert_eq!(
+        observed.headers,
+        ["change", "day", "part", "time", "answer"]
+    );
+    let changes = observed.rows.iter().map(|r| r[0].as_str()).collect_vec();
+    assert_eq!(changes, ["changed", "removed", "removed", "added"]);
+    assert_eq!(observed.rows[0], ["changed", "1", "1", "5 -> 6", " -> 142"]);
+    let error = times.diff(&times.concat(&times), &["day", "part"]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Key [\"1\", \"1\"] is in the new table more than once."
+    );
+    Ok(())
+}
+
+#[test]
+fn layout() -> Result<(), Report> {
+    let mut table = Table::new();
+    table.headers = ["y", "a", "b", "c"].map(String::from).to_vec();
+    table.rows = (0..5)
+        .map(|y| vec![y.to_string(), "xx".into(), "long cell".into(), "z".into()])
+        .collect_vec();
+
+    assert_eq!(truncate("日本語", 5), "日本…");
+    let layout = Layout {
+        width: Some(20),
+        cell_width: Some(6),
+        rows: Some(2),
+        page: 0,
+    };
+    let observed = table.to_layout(Format::Markdown, &layout)?;
+    let expected = "\
+| y | a  |   b    |
+|--:|:--:|:------:|
+| 0 | xx | long … |
+| 1 | xx | long … |
+
+| y | c |
+|--:|:-:|
+| 0 | z |
+| 1 | z |
+… rows 1-2 of 5
+";
+    assert_eq!(observed, expected);
+    assert!(observed.lines().all(|l| l.width() <= 20));
+
+    // the last page is short, and other formats don't get a summary line
+    let layout = Layout { page: 2, ..layout };
+    assert_eq!(
+        table.to_layout(Format::Csv, &layout)?,
+        "y,a,b,c\n4,xx,long cell,z\n"
+    );
+    let layout = Layout { page: 3, ..layout };
+    let error = table.to_layout(Format::Csv, &layout).unwrap_err();
+    assert_eq!(error.to_string(), "Page 4 is past the last page, 3.");
+
+    // a limit of 0 doesn't page at all
+    let layout = Layout {
+        rows: Some(0),
+        page: 0,
+        ..layout
+    };
+    let observed = table.to_layout(Format::Csv, &layout)?;
+    assert_eq!(observed.lines().count(), 6);
+    Ok(())
+}